@@ -0,0 +1,33 @@
+//! Measures how many sudokus per second `solve_fast` can check, run with `cargo bench`
+
+use std::{hint::black_box, time::Instant};
+use sudoku::{solver::solve_fast, Sudoku};
+
+const PUZZLES: &[&str] = &[
+    // Hard, unique
+    "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........",
+    "1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1",
+    "8..........36......7..9.2...5...7.......457.....1...3...1....68..85...1..9....4..",
+    "..............3.85..1.2.......5.7.....4...1...9.......5......73..2.1........4...9",
+    // Easy and minimal, like those checked by the generator
+    "49......838..........6............3.574.6.........7..29...18.6...83...4..6.4.51.7",
+    ".39.....4.42.1.3..7..34...9.74.5..3.3..4....5.9...348...3.7.2.14...3..7..175....3",
+    // Non-unique and unsolvable
+    ".................................................................................",
+    "4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4..........",
+    "12345678.........9...............................................................",
+];
+const ROUNDS: usize = 100;
+
+fn main() {
+    let sudokus: Vec<_> = PUZZLES.iter().map(|line| Sudoku::from_line(line).unwrap()).collect();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for sudoku in &sudokus {
+            black_box(solve_fast(black_box(sudoku)));
+        }
+    }
+    let elapsed = start.elapsed();
+    let solves = ROUNDS * sudokus.len();
+    println!("solve_fast: {} solves in {:.2?}, {:.0} solves/s", solves, elapsed, solves as f64 / elapsed.as_secs_f64());
+}
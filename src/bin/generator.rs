@@ -1,3 +1,168 @@
+use sudoku::{
+    generator::{self, DifficultyTarget, GenerateOpts, Symmetry},
+    solver::{SolveOpts, Strategy},
+};
+use std::{
+    env,
+    io::{self, Write},
+    process,
+};
+
+const USAGE: &str = "Usage: generator [OPTIONS]
+
+Generates uniquely solvable sudokus, printing one per line.
+
+Options:
+  -n, --count N           Number of sudokus to generate (default 1)
+  -r, --seed SEED         Seed the random number generator, the Nth sudoku uses SEED + N
+  -s, --strategies LIST   Strategies used to check the sudoku stays unique: ALL, FAST, or a comma
+                          separated list such as NakedSingle,HiddenSingle (default is a
+                          dedicated backtracking solver)
+  -f, --format FORMAT     Output format: line or pencilmarks (default line)
+  -y, --symmetry SYM      Symmetry of the givens: none, rotational180, rotational90, horizontal,
+                          vertical, diagonal, antidiagonal or dihedral (default none)
+      --hardest STRATEGY  Only output sudokus where this is the hardest strategy needed
+      --min-score SCORE   Only output sudokus rated at least this difficult
+      --max-score SCORE   Only output sudokus rated at most this difficult
+      --no-guess          Only output sudokus which can be solved without guess and check
+      --attempts N        With any of the above, give up after generating N sudokus which don't
+                          match (default 1000)
+  -h, --help              Print this message";
+
+enum Format {
+    Line,
+    Pencilmarks,
+}
+
+struct Args {
+    count: usize,
+    seed: Option<u64>,
+    strategies: Option<Vec<Strategy>>,
+    format: Format,
+    symmetry: Symmetry,
+    target: Option<DifficultyTarget>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        count: 1,
+        seed: None,
+        strategies: None,
+        format: Format::Line,
+        symmetry: Symmetry::None,
+        target: None,
+    };
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("Missing argument to {}", arg));
+        match arg.as_str() {
+            "-n" | "--count" => {
+                let value = value()?;
+                args.count = value.parse().map_err(|_| format!("Invalid count {:?}", value))?;
+            },
+            "-r" | "--seed" => {
+                let value = value()?;
+                args.seed = Some(value.parse().map_err(|_| format!("Invalid seed {:?}", value))?);
+            },
+            "-s" | "--strategies" => {
+                let value = value()?;
+                let strategies = Strategy::parse_list(&value).map_err(|e| format!("Invalid strategies {:?}: {:?}", value, e))?;
+                args.strategies = Some(strategies);
+            },
+            "-f" | "--format" => {
+                args.format = match value()?.as_str() {
+                    "line" => Format::Line,
+                    "pencilmarks" => Format::Pencilmarks,
+                    other => return Err(format!("Unknown format {:?}", other)),
+                };
+            },
+            "-y" | "--symmetry" => {
+                args.symmetry = match value()?.as_str() {
+                    "none" => Symmetry::None,
+                    "rotational180" => Symmetry::Rotational180,
+                    "rotational90" => Symmetry::Rotational90,
+                    "horizontal" => Symmetry::Horizontal,
+                    "vertical" => Symmetry::Vertical,
+                    "diagonal" => Symmetry::Diagonal,
+                    "antidiagonal" => Symmetry::AntiDiagonal,
+                    "dihedral" => Symmetry::Dihedral,
+                    other => return Err(format!("Unknown symmetry {:?}", other)),
+                };
+            },
+            "--hardest" => {
+                let value = value()?;
+                let strat = value.parse().map_err(|e| format!("Invalid strategy {:?}: {:?}", value, e))?;
+                args.target.get_or_insert_with(Default::default).hardest_strategy = Some(strat);
+            },
+            "--min-score" => {
+                let value = value()?;
+                let score = value.parse().map_err(|_| format!("Invalid score {:?}", value))?;
+                args.target.get_or_insert_with(Default::default).min_score = Some(score);
+            },
+            "--max-score" => {
+                let value = value()?;
+                let score = value.parse().map_err(|_| format!("Invalid score {:?}", value))?;
+                args.target.get_or_insert_with(Default::default).max_score = Some(score);
+            },
+            "--no-guess" => args.target.get_or_insert_with(Default::default).allow_guess_and_check = false,
+            "--attempts" => {
+                let value = value()?;
+                let attempts = value.parse().map_err(|_| format!("Invalid attempts {:?}", value))?;
+                args.target.get_or_insert_with(Default::default).max_attempts = attempts;
+            },
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            },
+            _ => return Err(format!("Unknown option {}", arg)),
+        }
+    }
+    Ok(args)
+}
+
 fn main() {
-    unimplemented!()
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for i in 0..args.count {
+        let base_opts = match args.seed {
+            Some(seed) => GenerateOpts::from_seed(seed.wrapping_add(i as u64)),
+            None => Default::default(),
+        };
+        let opts = GenerateOpts {
+            solve_opts: args.strategies.as_ref().map(|strategies| SolveOpts {
+                strategies,
+                guess_and_check: true,
+                stop_after_first_step: false,
+                uniqueness_strategies: false,
+            }),
+            symmetry: args.symmetry,
+            ..base_opts
+        };
+        let sudoku = match &args.target {
+            Some(target) => match generator::generate_with_target(opts, target) {
+                Some(sudoku) => sudoku,
+                None => {
+                    eprintln!("No sudoku matching the target was found, try more --attempts or a looser target");
+                    process::exit(1);
+                }
+            },
+            None => generator::generate(opts),
+        };
+        let res = match args.format {
+            Format::Line => writeln!(out, "{}", sudoku.to_line()),
+            Format::Pencilmarks => writeln!(out, "{}\n", sudoku.to_pencilmarks()),
+        };
+        if let Err(e) = res {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    }
 }
@@ -1,3 +1,169 @@
+use sudoku::{
+    solver::{self, SolveOpts, Strategy},
+    Sudoku,
+};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    process,
+};
+
+const USAGE: &str = "Usage: solver [OPTIONS] [FILE]...
+
+Solves each sudoku read from FILEs (or stdin if none are given), printing the solution followed by
+whether it was unique, non-unique or unsolvable.
+
+Options:
+  -p, --pencilmarks       Read pencilmark grids separated by blank lines, instead of one sudoku per
+                          line
+  -s, --strategies LIST   Strategies to solve with: ALL, FAST, or a comma separated list such as
+                          NakedSingle,HiddenSingle,MultiColor(3) (default ALL)
+  -n, --no-guess          Don't guess and check when the strategies are not enough
+  -a, --assume-unique     Use strategies which assume the sudoku has a unique solution. These can
+                          wrongly report a sudoku with several solutions as unique
+  -l, --log               Print each step taken to solve the sudoku
+  -c, --count LIMIT       Instead of solving, print the number of solutions, counting at most LIMIT
+  -h, --help              Print this message";
+
+struct Args {
+    pencilmarks: bool,
+    strategies: Vec<Strategy>,
+    guess_and_check: bool,
+    uniqueness_strategies: bool,
+    log: bool,
+    count_limit: Option<usize>,
+    files: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        pencilmarks: false,
+        strategies: solver::ALL.to_vec(),
+        guess_and_check: true,
+        uniqueness_strategies: false,
+        log: false,
+        count_limit: None,
+        files: Vec::new(),
+    };
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-p" | "--pencilmarks" => args.pencilmarks = true,
+            "-s" | "--strategies" => {
+                let list = iter.next().ok_or_else(|| format!("Missing argument to {}", arg))?;
+                args.strategies = Strategy::parse_list(&list).map_err(|e| format!("Invalid strategies {:?}: {:?}", list, e))?;
+            },
+            "-n" | "--no-guess" => args.guess_and_check = false,
+            "-a" | "--assume-unique" => args.uniqueness_strategies = true,
+            "-l" | "--log" => args.log = true,
+            "-c" | "--count" => {
+                let limit = iter.next().ok_or_else(|| format!("Missing argument to {}", arg))?;
+                args.count_limit = Some(limit.parse().map_err(|_| format!("Invalid limit {:?}", limit))?);
+            },
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            },
+            "-" => args.files.push(arg),
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {}", arg)),
+            _ => args.files.push(arg),
+        }
+    }
+    Ok(args)
+}
+
+/// Splits the input into the text of each sudoku, along with the line number it started on
+fn read_puzzles(reader: impl BufRead, pencilmarks: bool) -> io::Result<Vec<(usize, String)>> {
+    let mut puzzles = Vec::new();
+    let mut current: Option<(usize, String)> = None;
+    for (idx, line) in reader.lines().enumerate() {
+        let line = line?;
+        let trimmed = line.trim();
+        if trimmed.starts_with('#') { continue }
+        if !pencilmarks {
+            if !trimmed.is_empty() {
+                puzzles.push((idx + 1, trimmed.to_string()));
+            }
+        } else if trimmed.is_empty() {
+            puzzles.extend(current.take());
+        } else {
+            let (_, text) = current.get_or_insert_with(|| (idx + 1, String::new()));
+            text.push_str(&line);
+            text.push('\n');
+        }
+    }
+    puzzles.extend(current);
+    Ok(puzzles)
+}
+
+fn solve_puzzles(name: &str, reader: impl BufRead, args: &Args, out: &mut impl Write) -> io::Result<bool> {
+    let opts = SolveOpts {
+        strategies: &args.strategies,
+        guess_and_check: args.guess_and_check,
+        stop_after_first_step: false,
+        uniqueness_strategies: args.uniqueness_strategies,
+    };
+    let mut all_ok = true;
+    for (line_num, text) in read_puzzles(reader, args.pencilmarks)? {
+        let parsed = if args.pencilmarks {
+            Sudoku::from_pencilmarks(&text)
+        } else {
+            Sudoku::from_line(&text)
+        };
+        let sudoku = match parsed {
+            Ok(sudoku) => sudoku,
+            Err(e) => {
+                eprintln!("{}:{}: Failed to parse sudoku: {:?}", name, line_num, e);
+                all_ok = false;
+                continue
+            }
+        };
+
+        if let Some(limit) = args.count_limit {
+            writeln!(out, "{} {}", sudoku.to_line(), solver::count_solutions(&sudoku, limit))?;
+            continue
+        }
+
+        let res = solver::solve(sudoku, &opts);
+        writeln!(out, "{} {:?}", res.sudoku.to_line(), res.success)?;
+        if args.log {
+            for step in &res.steps {
+                writeln!(out, "  {}", step)?;
+            }
+        }
+    }
+    Ok(all_ok)
+}
+
 fn main() {
-    unimplemented!()
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let files = if args.files.is_empty() { vec!["-".to_string()] } else { args.files.clone() };
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut all_ok = true;
+    for name in &files {
+        let res = if name == "-" {
+            solve_puzzles("<stdin>", io::stdin().lock(), &args, &mut out)
+        } else {
+            File::open(name).and_then(|file| solve_puzzles(name, BufReader::new(file), &args, &mut out))
+        };
+        match res {
+            Ok(ok) => all_ok &= ok,
+            Err(e) => {
+                eprintln!("{}: {}", name, e);
+                all_ok = false;
+            }
+        }
+    }
+    if !all_ok {
+        process::exit(1);
+    }
 }
@@ -1,25 +1,98 @@
 use crate::{
-    solver::{solve, SolveOpts, SolveResult, SolveSuccess, Row, Col, Block},
+    solver::{rate, solve, solve_fast, Rating, SolveOpts, SolveResult, SolveSuccess, Strategy, Row, Col, Block},
     Pos, Sudoku,
 };
 use rand::{
     seq::SliceRandom,
-    thread_rng, RngCore,
+    thread_rng, RngCore, SeedableRng,
 };
+use rand_chacha::ChaCha8Rng;
+use std::mem::discriminant;
+
+mod symmetry;
+
+pub use symmetry::Symmetry;
 
-// FIXME - add symmetry
 pub struct GenerateOpts<'a> {
-    pub solve_opts: SolveOpts<'a>,
-    pub rng: Box<dyn RngCore>
+    /// Strategies used to check the sudoku stays uniquely solvable while removing givens, or
+    /// `None` to use [`solve_fast`], which is much faster and finds the same sudokus
+    pub solve_opts: Option<SolveOpts<'a>>,
+    pub rng: Box<dyn RngCore>,
+    /// Givens are removed in sets which keep this symmetry
+    pub symmetry: Symmetry,
 }
 
 impl Default for GenerateOpts<'_> {
     #[inline]
     fn default() -> Self {
         Self {
-            solve_opts: SolveOpts::fast(),
-            rng: Box::new(thread_rng())
+            solve_opts: None,
+            rng: Box::new(thread_rng()),
+            symmetry: Symmetry::None,
+        }
+    }
+}
+
+impl GenerateOpts<'_> {
+    /// Uses a seeded random number generator, so the same seed always generates the same sudoku
+    /// (for the same version of this crate and the same options).
+    pub fn from_seed(seed: u64) -> Self {
+        Self {
+            rng: Box::new(ChaCha8Rng::seed_from_u64(seed)),
+            ..Default::default()
+        }
+    }
+}
+
+/// Constraints on the [`Rating`] of a generated sudoku
+#[derive(Debug, Clone, PartialEq)]
+pub struct DifficultyTarget {
+    /// The hardest strategy needed to solve the sudoku. Parameters are ignored when comparing, so
+    /// e.g. `MultiColor(2)` matches any `MultiColor`.
+    pub hardest_strategy: Option<Strategy>,
+    /// Whether sudokus which can't be solved without guess and check are allowed
+    pub allow_guess_and_check: bool,
+    pub min_score: Option<f32>,
+    pub max_score: Option<f32>,
+    /// How many sudokus to generate before giving up, as some targets are rare or impossible
+    pub max_attempts: usize
+}
+
+impl Default for DifficultyTarget {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            hardest_strategy: None,
+            allow_guess_and_check: true,
+            min_score: None,
+            max_score: None,
+            max_attempts: 1000
+        }
+    }
+}
+
+impl DifficultyTarget {
+    pub fn matches(&self, rating: &Rating) -> bool {
+        if let Some(strat) = self.hardest_strategy {
+            match rating.hardest_strategy {
+                Some(hardest) if discriminant(&hardest) == discriminant(&strat) => (),
+                _ => return false
+            }
         }
+        if self.min_score.is_some_and(|min| rating.score < min) { return false }
+        !self.too_hard(rating)
+    }
+
+    /// Whether the target puts an upper bound on the difficulty, so [`Self::too_hard`] can ever be
+    /// true
+    fn has_upper_bound(&self) -> bool {
+        !self.allow_guess_and_check || self.max_score.is_some()
+    }
+
+    /// Removing more givens rarely makes a sudoku easier, so once this is true we can stop
+    fn too_hard(&self, rating: &Rating) -> bool {
+        (!self.allow_guess_and_check && rating.guesses > 0) ||
+            self.max_score.is_some_and(|max| rating.score > max)
     }
 }
 
@@ -79,10 +152,10 @@ fn generate_solved(rng: &mut dyn RngCore) -> Sudoku {
     random_guess_and_check_to_fill(sudoku, rng).unwrap()
 }
 
-fn sudoku_without_given(sudoku: &Sudoku, removed_pos: Pos) -> Sudoku {
+fn sudoku_without_givens(sudoku: &Sudoku, removed_positions: &[Pos]) -> Sudoku {
     let mut givens = Vec::new();
     for pos in Pos::iter() {
-        if pos == removed_pos { continue }
+        if removed_positions.contains(&pos) { continue }
         match sudoku.get_value(pos) {
             Some(val) => givens.push((pos, val)),
             None => ()
@@ -95,24 +168,51 @@ fn sudoku_without_given(sudoku: &Sudoku, removed_pos: Pos) -> Sudoku {
     sudoku
 }
 
-pub fn generate(mut opts: GenerateOpts) -> Sudoku {
+fn generate_minimal(opts: &mut GenerateOpts, target: Option<&DifficultyTarget>) -> Sudoku {
     let mut sudoku = generate_solved(&mut opts.rng);
+    // Only targets with an upper bound need rating while removing givens, others are rated once at
+    // the end
+    let target = target.filter(|target| target.has_upper_bound());
 
-    // Remove random givens as long as the sudoku stays uniquely solvable
-    let mut positions: Vec<_> = Pos::iter().collect();
-    positions.shuffle(&mut opts.rng);
-    while let Some(pos) = positions.pop() {
-        if sudoku.get_value(pos).is_none() { continue }
-        let sudoku2 = sudoku_without_given(&sudoku, pos);
-        match solve(sudoku2.clone(), &opts.solve_opts) {
+    // Remove random givens as long as the sudoku stays uniquely solvable (and not too hard). To
+    // keep the symmetry, all positions that map onto each other are removed together.
+    let mut orbits = opts.symmetry.orbits();
+    orbits.shuffle(&mut opts.rng);
+    while let Some(orbit) = orbits.pop() {
+        let sudoku2 = sudoku_without_givens(&sudoku, &orbit);
+        let res = match &opts.solve_opts {
+            // We're checking whether the sudoku is unique, so can't assume it is
+            Some(solve_opts) => solve(sudoku2.clone(), &SolveOpts { uniqueness_strategies: false, ..*solve_opts }),
+            None => solve_fast(&sudoku2),
+        };
+        match res {
             SolveResult { success: SolveSuccess::NonUnique, .. } => (),
-            SolveResult { success: SolveSuccess::Unique, .. } => sudoku = sudoku2,
+            SolveResult { success: SolveSuccess::Unique, .. } => {
+                if let Some(target) = target {
+                    if target.too_hard(&rate(&sudoku2)) { continue }
+                }
+                sudoku = sudoku2;
+            },
             SolveResult { success: SolveSuccess::Unsolvable, .. } => unreachable!()
         };
     }
     sudoku
 }
 
+/// Generates a uniquely solvable sudoku
+pub fn generate(mut opts: GenerateOpts) -> Sudoku {
+    generate_minimal(&mut opts, None)
+}
+
+/// Generates a uniquely solvable sudoku with the target difficulty, retrying until one is found.
+/// Returns `None` if none is found in [`DifficultyTarget::max_attempts`] tries. Each try is rated
+/// by solving it with [`crate::solver::ALL`], so rare targets can take a long time.
+pub fn generate_with_target(mut opts: GenerateOpts, target: &DifficultyTarget) -> Option<Sudoku> {
+    (0 .. target.max_attempts)
+        .map(|_| generate_minimal(&mut opts, Some(target)))
+        .find(|sudoku| target.matches(&rate(sudoku)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,4 +223,62 @@ mod tests {
         assert!(!Pos::iter().all(|pos| sudoku.get_value(pos).is_some()));
         assert!(solve(sudoku, &SolveOpts::fast()).is_unique());
     }
+
+    #[test]
+    fn test_generate_seeded() {
+        let sudoku = generate(GenerateOpts::from_seed(42));
+        assert_eq!(sudoku, generate(GenerateOpts::from_seed(42)));
+        assert_ne!(sudoku, generate(GenerateOpts::from_seed(43)));
+        // Changing this breaks reproducibility for anyone who published a seed
+        assert_eq!(generate(GenerateOpts::from_seed(0)).to_line(), "49......838..........6............3.574.6.........7..29...18.6...83...4..6.4.51.7");
+    }
+
+    #[test]
+    fn test_generate_symmetry() {
+        for &symmetry in &[Symmetry::Rotational180, Symmetry::Rotational90, Symmetry::Horizontal,
+            Symmetry::Vertical, Symmetry::Diagonal, Symmetry::AntiDiagonal, Symmetry::Dihedral]
+        {
+            let sudoku = generate(GenerateOpts { symmetry, ..Default::default() });
+            assert!(solve(sudoku.clone(), &SolveOpts::fast()).is_unique());
+            for orbit in symmetry.orbits() {
+                let num_given = orbit.iter().filter(|&&pos| sudoku.get_value(pos).is_some()).count();
+                assert!(num_given == 0 || num_given == orbit.len(), "{:?} not symmetric:\n{:?}", symmetry, sudoku);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generate_target() {
+        let target = DifficultyTarget {
+            hardest_strategy: Some(Strategy::LockedCandidate),
+            allow_guess_and_check: false,
+            min_score: None,
+            max_score: Some(2.8),
+            max_attempts: 1000
+        };
+        let sudoku = generate_with_target(Default::default(), &target).unwrap();
+        let rating = rate(&sudoku);
+        assert!(target.matches(&rating));
+        assert!(matches!(rating.hardest_strategy, Some(Strategy::LockedCandidate)));
+    }
+
+    #[test]
+    fn test_generate_unreachable_target() {
+        let target = DifficultyTarget { min_score: Some(100.0), max_attempts: 3, ..Default::default() };
+        assert_eq!(generate_with_target(Default::default(), &target), None);
+    }
+
+    #[test]
+    fn test_target_matches() {
+        let sudoku = Sudoku::from_line("9812763542639541..574183692315..8..9428..95..796..5...642597813157832946839641725").unwrap();
+        let rating = rate(&sudoku);
+        let target = DifficultyTarget { hardest_strategy: Some(Strategy::XyWing), ..Default::default() };
+        assert!(target.matches(&rating));
+        let target = DifficultyTarget { hardest_strategy: Some(Strategy::XyzWing), ..Default::default() };
+        assert!(!target.matches(&rating));
+        let target = DifficultyTarget { min_score: Some(4.0), max_score: Some(4.5), ..Default::default() };
+        assert!(target.matches(&rating));
+        let target = DifficultyTarget { max_score: Some(4.0), ..Default::default() };
+        assert!(!target.matches(&rating));
+    }
 }
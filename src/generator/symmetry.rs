@@ -0,0 +1,113 @@
+use crate::Pos;
+
+/// The symmetry of the givens in a generated sudoku. Mirrors are named after the axis they flip
+/// over.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    None,
+    /// Rotating by 180° about the center cell
+    Rotational180,
+    /// Rotating by 90° about the center cell (which implies 180° as well)
+    Rotational90,
+    /// Mirrored over the middle row
+    Horizontal,
+    /// Mirrored over the middle column
+    Vertical,
+    /// Mirrored over the diagonal from r1c1 to r9c9
+    Diagonal,
+    /// Mirrored over the diagonal from r1c9 to r9c1
+    AntiDiagonal,
+    /// All of the rotations and mirrors at once
+    Dihedral,
+}
+
+fn rotate_90(pos: Pos) -> Pos {
+    Pos::row_col(pos.col(), 8 - pos.row())
+}
+
+fn rotate_180(pos: Pos) -> Pos {
+    Pos::row_col(8 - pos.row(), 8 - pos.col())
+}
+
+fn mirror_horizontal(pos: Pos) -> Pos {
+    Pos::row_col(8 - pos.row(), pos.col())
+}
+
+fn mirror_vertical(pos: Pos) -> Pos {
+    Pos::row_col(pos.row(), 8 - pos.col())
+}
+
+fn mirror_diagonal(pos: Pos) -> Pos {
+    Pos::row_col(pos.col(), pos.row())
+}
+
+fn mirror_anti_diagonal(pos: Pos) -> Pos {
+    Pos::row_col(8 - pos.col(), 8 - pos.row())
+}
+
+impl Symmetry {
+    /// The transforms which generate the symmetry group
+    fn generators(self) -> &'static [fn(Pos) -> Pos] {
+        match self {
+            Symmetry::None => &[],
+            Symmetry::Rotational180 => &[rotate_180],
+            Symmetry::Rotational90 => &[rotate_90],
+            Symmetry::Horizontal => &[mirror_horizontal],
+            Symmetry::Vertical => &[mirror_vertical],
+            Symmetry::Diagonal => &[mirror_diagonal],
+            Symmetry::AntiDiagonal => &[mirror_anti_diagonal],
+            Symmetry::Dihedral => &[rotate_90, mirror_vertical],
+        }
+    }
+
+    /// Partitions all positions into the sets of positions which map onto each other, so must be
+    /// all given or all empty.
+    pub(crate) fn orbits(self) -> Vec<Vec<Pos>> {
+        let mut seen = [false; Pos::N];
+        let mut ret = Vec::new();
+        for pos in Pos::iter() {
+            if seen[pos.as_usize()] { continue }
+            seen[pos.as_usize()] = true;
+            let mut orbit = vec![pos];
+            let mut idx = 0;
+            while idx < orbit.len() {
+                for transform in self.generators() {
+                    let pos2 = transform(orbit[idx]);
+                    if !seen[pos2.as_usize()] {
+                        seen[pos2.as_usize()] = true;
+                        orbit.push(pos2);
+                    }
+                }
+                idx += 1;
+            }
+            ret.push(orbit);
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orbits() {
+        let center = Pos::row_col(4, 4);
+        let corner = Pos::row_col(0, 0);
+        let orbit_len = |sym: Symmetry, pos: Pos| {
+            sym.orbits().into_iter().find(|orbit| orbit.contains(&pos)).unwrap().len()
+        };
+        for &(sym, num_orbits) in &[
+            (Symmetry::None, 81), (Symmetry::Rotational180, 41), (Symmetry::Rotational90, 21),
+            (Symmetry::Horizontal, 45), (Symmetry::Vertical, 45), (Symmetry::Diagonal, 45),
+            (Symmetry::AntiDiagonal, 45), (Symmetry::Dihedral, 15),
+        ] {
+            assert_eq!(sym.orbits().len(), num_orbits, "{:?}", sym);
+            assert_eq!(orbit_len(sym, center), 1);
+        }
+        assert_eq!(orbit_len(Symmetry::Rotational90, corner), 4);
+        assert_eq!(orbit_len(Symmetry::Dihedral, corner), 4);
+        assert_eq!(orbit_len(Symmetry::Dihedral, Pos::row_col(0, 1)), 8);
+        assert_eq!(orbit_len(Symmetry::Diagonal, corner), 1);
+    }
+}
@@ -2,8 +2,10 @@ use crate::{
     pos::{Pos, PosBitSet},
     solver::Line,
 };
+use std::fmt;
 
-pub(crate) struct Block(u8);
+/// Blocks are numbered in the same way as positions, see [`Pos::block`]
+pub struct Block(u8);
 
 impl_index_type!(Block(u8), 9);
 impl_type_indexed_slice!(BlockIndexedSlice, Block, pub(crate));
@@ -21,16 +23,23 @@ impl Block {
 
     #[allow(dead_code)]
     #[inline]
-    pub fn members_bitset(&self) -> PosBitSet {
+    pub(crate) fn members_bitset(&self) -> PosBitSet {
         MEMBER_BITSETS[*self]
     }
 
+    /// The rows and columns which pass through this block
     #[inline]
     pub fn intersecting_lines_iter(&self) -> impl Iterator<Item = Line> {
         INTERSECTING_LINES[*self].iter().cloned()
     }
 }
 
+impl fmt::Display for Block {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "block {}", self.0 + 1)
+    }
+}
+
 #[static_init::dynamic]
 static MEMBER_VECS: BlockIndexedSlice<Vec<Pos>> = {
     const EMPTY_VEC: Vec<Pos> = Vec::new(); // Workaround for array initialization
@@ -1,8 +1,9 @@
 use crate::{
     pos::{Pos, PosBitSet},
 };
+use std::fmt;
 
-pub(crate) struct Col(u8);
+pub struct Col(u8);
 
 impl_index_type!(Col(u8), 9);
 impl_type_indexed_slice!(ColIndexedSlice, Col, pub(crate));
@@ -20,11 +21,17 @@ impl Col {
 
     #[allow(dead_code)]
     #[inline]
-    pub fn members_bitset(&self) -> PosBitSet {
+    pub(crate) fn members_bitset(&self) -> PosBitSet {
         MEMBER_BITSETS[*self]
     }
 }
 
+impl fmt::Display for Col {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "col {}", self.0 + 1)
+    }
+}
+
 #[static_init::dynamic]
 static MEMBER_VECS: ColIndexedSlice<Vec<Pos>> = {
     const EMPTY_POS_VEC: Vec<Pos> = Vec::new(); // Workaround for array initialization
@@ -0,0 +1,438 @@
+use crate::{
+    solver::{SolveResult, SolveSuccess},
+    Pos, Sudoku, Value,
+};
+
+// The board is stored as a bitboard per value and band (three rows), with one bit per cell
+// numbered row by row, so bit `i` of band `b` is the cell at `Pos::new(b * 27 + i)`. A value must
+// be placed exactly once in each row and block of a band, which only 6 patterns of segments (the
+// 3 cells of a row within a block) allow. Checking these patterns with the lookup tables below
+// finds locked candidates within the band for little more than the cost of finding singles, and
+// the same is done for the cols and blocks of each stack.
+//
+// Lookup tables are built at compile time so the solver never has to touch the lazily built
+// statics used elsewhere.
+
+type Band = u32;
+
+const ROW_MASK: Band = (1 << 9) - 1;
+
+const fn build_blocks() -> [Band; 3] {
+    let mut ret = [0; 3];
+    let mut i = 0;
+    while i < 27 {
+        ret[(i % 9) / 3] |= 1 << i;
+        i += 1;
+    }
+    ret
+}
+
+const fn build_cols() -> [Band; 9] {
+    let mut ret = [0; 9];
+    let mut col = 0;
+    while col < 9 {
+        ret[col] = 1 << col | 1 << (col + 9) | 1 << (col + 18);
+        col += 1;
+    }
+    ret
+}
+
+/// The other cells in the same row, col or block of the band
+const fn build_neighbors() -> [Band; 27] {
+    let mut ret = [0; 27];
+    let mut i = 0;
+    while i < 27 {
+        ret[i] = (ROW_MASK << (i / 9 * 9) | COLS[i % 9] | BLOCKS[(i % 9) / 3]) & !(1 << i);
+        i += 1;
+    }
+    ret
+}
+
+/// For each row of 9 cells, which of its 3 segments contain a cell
+const fn build_shrink() -> [u16; 512] {
+    let mut ret = [0; 512];
+    let mut row = 0;
+    while row < 512 {
+        let mut segment = 0;
+        while segment < 3 {
+            if row & (7 << (segment * 3)) != 0 { ret[row] |= 1 << segment }
+            segment += 1;
+        }
+        row += 1;
+    }
+    ret
+}
+
+/// For each set of non-empty segments in a band (bit `row * 3 + block`), the segments which
+/// are part of a pattern placing the value once in each row and block
+const fn build_allowed() -> [u16; 512] {
+    let mut ret = [0; 512];
+    let mut segments = 0;
+    while segments < 512 {
+        // The block used by the first and second row, the third row uses the remaining block
+        let mut first = 0;
+        while first < 3 {
+            let mut second = 0;
+            while second < 3 {
+                if second != first {
+                    let third = 3 - first - second;
+                    let pattern: u16 = 1 << first | 1 << (3 + second) | 1 << (6 + third);
+                    if segments as u16 & pattern == pattern { ret[segments] |= pattern }
+                }
+                second += 1;
+            }
+            first += 1;
+        }
+        segments += 1;
+    }
+    ret
+}
+
+/// The cells of each set of segments
+const fn build_expand() -> [Band; 512] {
+    let mut ret = [0; 512];
+    let mut segments = 0;
+    while segments < 512 {
+        let mut i = 0;
+        while i < 9 {
+            if segments & (1 << i) != 0 { ret[segments] |= 7 << ((i / 3) * 9 + (i % 3) * 3) }
+            i += 1;
+        }
+        segments += 1;
+    }
+    ret
+}
+
+/// Moves bits 0, 1 and 2 to bits 0, 3 and 6
+const SPREAD: [u16; 8] = [0, 1, 1 << 3, 1 | 1 << 3, 1 << 6, 1 | 1 << 6, 1 << 3 | 1 << 6, 1 | 1 << 3 | 1 << 6];
+const BLOCKS: [Band; 3] = build_blocks();
+const COLS: [Band; 9] = build_cols();
+const NEIGHBORS: [Band; 27] = build_neighbors();
+const SHRINK: [u16; 512] = build_shrink();
+const ALLOWED: [u16; 512] = build_allowed();
+const EXPAND: [Band; 512] = build_expand();
+
+/// Which cells of a band have at least one, at least two and at least three candidates
+struct CandidateCounts {
+    one: Band,
+    two: Band,
+    three: Band,
+}
+
+/// The state of the board while backtracking, small enough to copy at each guess
+#[derive(Copy, Clone)]
+struct Board {
+    /// For each value and band, the unsolved cells it is a candidate in and the solved cells it
+    /// is placed in
+    values: [[Band; 3]; 9],
+    unsolved: [Band; 3],
+}
+
+impl Board {
+    const EMPTY: Board = Board { values: [[0; 3]; 9], unsolved: [0; 3] };
+
+    /// Returns `None` if some givens see each other
+    fn from_sudoku(sudoku: &Sudoku) -> Option<Self> {
+        let mut ret = Board::EMPTY;
+        for pos in Pos::iter() {
+            let (band, bit) = (pos.as_usize() / 27, 1 << (pos.as_usize() % 27));
+            if sudoku.get_value(pos).is_none() {
+                ret.unsolved[band] |= bit;
+                for val in sudoku.get_candidates_iter(pos) {
+                    ret.values[val.as_usize()][band] |= bit;
+                }
+            }
+        }
+        let givens = || Pos::iter().filter_map(|pos| {
+            sudoku.get_value(pos).map(|val| (pos.as_usize() / 27, pos.as_usize() % 27, val.as_usize()))
+        });
+        for (band, i, val) in givens() {
+            ret.place(band, i, val);
+        }
+        // Placing a given removes any other given with the same value it sees
+        if givens().any(|(band, i, val)| ret.values[val][band] & (1 << i) == 0) { return None }
+        Some(ret)
+    }
+
+    /// The value of a solved cell
+    #[inline]
+    fn value(&self, band: usize, i: usize) -> usize {
+        self.values.iter().position(|bands| bands[band] & (1 << i) != 0).unwrap()
+    }
+
+    #[inline]
+    fn place(&mut self, band: usize, i: usize, val: usize) {
+        let bit = 1 << i;
+        for bands in &mut self.values {
+            bands[band] &= !bit;
+        }
+        let bands = &mut self.values[val];
+        for (other, cells) in bands.iter_mut().enumerate() {
+            *cells &= !if other == band { NEIGHBORS[i] } else { COLS[i % 9] };
+        }
+        bands[band] |= bit;
+        self.unsolved[band] &= !bit;
+    }
+
+    #[inline]
+    fn candidate_counts(&self, band: usize) -> CandidateCounts {
+        let mut counts = CandidateCounts { one: 0, two: 0, three: 0 };
+        for bands in &self.values {
+            let cells = bands[band] & self.unsolved[band];
+            counts.three |= counts.two & cells;
+            counts.two |= counts.one & cells;
+            counts.one |= cells;
+        }
+        counts
+    }
+
+    /// Removes candidates of the value which can't be part of any pattern placing it once in each
+    /// row and block of the band, then places its hidden singles in these rows and blocks. Returns
+    /// false on a contradiction.
+    #[inline]
+    fn propagate_band(&mut self, band: usize, val: usize) -> bool {
+        let cells = self.values[val][band];
+        let segments = SHRINK[(cells & ROW_MASK) as usize]
+            | SHRINK[((cells >> 9) & ROW_MASK) as usize] << 3
+            | SHRINK[(cells >> 18) as usize] << 6;
+        let allowed = ALLOWED[segments as usize];
+        if allowed == 0 { return false }
+        self.values[val][band] = cells & EXPAND[allowed as usize];
+
+        for &house in &[ROW_MASK, ROW_MASK << 9, ROW_MASK << 18, BLOCKS[0], BLOCKS[1], BLOCKS[2]] {
+            // Placing a hidden single removes the other cells of its row and block, but never
+            // all cells of another row or block, as the remaining pattern still fits
+            let house_cells = self.values[val][band] & house;
+            if house_cells & self.unsolved[band] != 0 && house_cells & (house_cells - 1) == 0 {
+                self.place(band, house_cells.trailing_zeros() as usize, val);
+            }
+        }
+        true
+    }
+
+    /// Removes candidates of the value which can't be part of any pattern placing it once in each
+    /// col and block of the stack. Returns false on a contradiction.
+    #[inline]
+    fn propagate_stack(&mut self, stack: usize, val: usize) -> bool {
+        // Bit `col * 3 + band` is set if the col of the stack has a cell in the band
+        let mut segments = 0;
+        for (band, &cells) in self.values[val].iter().enumerate() {
+            let cols = ((cells | cells >> 9 | cells >> 18) >> (stack * 3)) & 7;
+            segments |= SPREAD[cols as usize] << band;
+        }
+        let allowed = ALLOWED[segments as usize];
+        if allowed == 0 { return false }
+        let mut disallowed = segments & !allowed;
+        while disallowed != 0 {
+            let i = disallowed.trailing_zeros() as usize;
+            disallowed &= disallowed - 1;
+            self.values[val][i % 3] &= !COLS[stack * 3 + i / 3];
+        }
+        true
+    }
+
+    /// Places hidden singles of the value in cols. Returns false on a contradiction.
+    fn propagate_cols(&mut self, val: usize) -> bool {
+        let (mut one, mut two, mut solved) = (0, 0, 0);
+        for (band, &cells) in self.values[val].iter().enumerate() {
+            let solved_cells = cells & !self.unsolved[band];
+            for row in 0..3 {
+                let row_cells = (cells >> (row * 9)) & ROW_MASK;
+                two |= one & row_cells;
+                one |= row_cells;
+                solved |= (solved_cells >> (row * 9)) & ROW_MASK;
+            }
+        }
+        if one != ROW_MASK { return false }
+        let mut singles = one & !two & !solved;
+        while singles != 0 {
+            let col = singles.trailing_zeros() as usize;
+            singles &= singles - 1;
+            // An earlier single in this loop may have removed the last candidate
+            let band = match (0..3).find(|&band| self.values[val][band] & COLS[col] != 0) {
+                Some(band) => band,
+                None => return false
+            };
+            self.place(band, (self.values[val][band] & COLS[col]).trailing_zeros() as usize, val);
+        }
+        true
+    }
+
+    /// Places naked singles. Returns false on a contradiction.
+    fn propagate_naked(&mut self) -> bool {
+        for band in 0..3 {
+            let counts = self.candidate_counts(band);
+            if self.unsolved[band] & !counts.one != 0 { return false }
+            let mut singles = counts.one & !counts.two;
+            while singles != 0 {
+                let i = singles.trailing_zeros() as usize;
+                singles &= singles - 1;
+                // An earlier single in this loop may have removed the last candidate
+                match (0..9).find(|&val| self.values[val][band] & self.unsolved[band] & (1 << i) != 0) {
+                    Some(val) => self.place(band, i, val),
+                    None => return false
+                }
+            }
+        }
+        true
+    }
+
+    /// Places singles and removes locked candidates until there are none left. Returns false on
+    /// a contradiction.
+    fn propagate(&mut self) -> bool {
+        // Bit `val * 3 + band` is set if the candidates of the value in the band changed since
+        // they were last checked
+        let mut changed: u32 = (1 << 27) - 1;
+        loop {
+            let before = self.values;
+            for val in 0..9 {
+                if (changed >> (val * 3)) & 7 == 0 { continue }
+                for band in 0..3 {
+                    if changed & (1 << (val * 3 + band)) != 0 && !self.propagate_band(band, val) { return false }
+                }
+                for stack in 0..3 {
+                    if !self.propagate_stack(stack, val) { return false }
+                }
+                if !self.propagate_cols(val) { return false }
+            }
+            if !self.propagate_naked() { return false }
+
+            changed = 0;
+            for (i, (cells, before)) in self.values.iter().flatten().zip(before.iter().flatten()).enumerate() {
+                if cells != before { changed |= 1 << i }
+            }
+            if changed == 0 { return true }
+        }
+    }
+
+    fn is_solved(&self) -> bool {
+        self.unsolved.iter().all(|&cells| cells == 0)
+    }
+
+    /// The unsolved cell with the fewest candidates, as (band, index in band)
+    fn best_guess_pos(&self) -> (usize, usize) {
+        let mut best = (0, 0);
+        let mut best_cnt = u32::MAX;
+        for band in 0..3 {
+            let counts = self.candidate_counts(band);
+            let bivalue = counts.two & !counts.three;
+            if bivalue != 0 { return (band, bivalue.trailing_zeros() as usize) }
+            let mut unsolved = self.unsolved[band];
+            while unsolved != 0 {
+                let i = unsolved.trailing_zeros() as usize;
+                unsolved &= unsolved - 1;
+                let cnt = self.values.iter().filter(|bands| bands[band] & (1 << i) != 0).count() as u32;
+                if cnt < best_cnt {
+                    best = (band, i);
+                    best_cnt = cnt;
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Solves the sudoku with a dedicated backtracking solver, which is much faster than [`solve`]
+/// but doesn't record any steps. Singles and locked candidates are used between guesses, and no
+/// allocations are made while solving, so this is suited to checking uniqueness in bulk.
+///
+/// [`solve`]: crate::solver::solve
+pub fn solve_fast(sudoku: &Sudoku) -> SolveResult {
+    let mut board = match Board::from_sudoku(sudoku) {
+        Some(board) => board,
+        None => return SolveResult { success: SolveSuccess::Unsolvable, sudoku: sudoku.clone(), steps: Vec::new() }
+    };
+    // Every guess removes a candidate from the board we backtrack to, so the depth is bounded by
+    // the number of cells
+    let mut stack = [Board::EMPTY; Pos::N];
+    let mut stack_len = 0;
+    let mut solution: Option<Board> = None;
+    let mut success = SolveSuccess::Unsolvable;
+
+    loop {
+        if board.propagate() {
+            if board.is_solved() {
+                if solution.is_some() {
+                    success = SolveSuccess::NonUnique;
+                    break
+                }
+                solution = Some(board);
+                success = SolveSuccess::Unique;
+            } else {
+                let (band, i) = board.best_guess_pos();
+                let val = (0..9).find(|&val| board.values[val][band] & (1 << i) != 0).unwrap();
+                // Come back to the board without this candidate once we're done with the guess
+                let mut other = board;
+                other.values[val][band] &= !(1 << i);
+                stack[stack_len] = other;
+                stack_len += 1;
+                board.place(band, i, val);
+                continue
+            }
+        }
+
+        if stack_len == 0 { break }
+        stack_len -= 1;
+        board = stack[stack_len];
+    }
+
+    let sudoku = match solution {
+        None => sudoku.clone(),
+        Some(solution) => {
+            let mut ret = sudoku.clone();
+            for pos in Pos::iter() {
+                if ret.get_value(pos).is_none() {
+                    let val = solution.value(pos.as_usize() / 27, pos.as_usize() % 27);
+                    ret.set_value(pos, Value::new(val));
+                }
+            }
+            ret
+        }
+    };
+    SolveResult { success, sudoku, steps: Vec::new() }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve, SolveOpts};
+
+    #[test]
+    fn test_solve_fast_unique() {
+        let sudoku = Sudoku::from_line("4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........").unwrap();
+        let res = solve_fast(&sudoku);
+        assert_eq!(res.success, SolveSuccess::Unique);
+        assert_eq!(res.sudoku, Sudoku::from_line("468931527751624839392578461134756298289413675675289314846192753513867942927345186").unwrap());
+    }
+
+    #[test]
+    fn test_solve_fast_non_unique() {
+        assert_eq!(solve_fast(&Sudoku::new()).success, SolveSuccess::NonUnique);
+        let two = Sudoku::from_line("4689.15.77516.48.9392578461134756298289413675675289314846192753513867942927345186").unwrap();
+        assert_eq!(solve_fast(&two).success, SolveSuccess::NonUnique);
+    }
+
+    #[test]
+    fn test_solve_fast_unsolvable() {
+        let sudoku = Sudoku::from_line("12345678.........9...............................................................").unwrap();
+        let res = solve_fast(&sudoku);
+        assert_eq!(res.success, SolveSuccess::Unsolvable);
+        assert_eq!(res.sudoku, sudoku);
+        let same_col = Sudoku::from_line("5..............................................................5.................").unwrap();
+        assert_eq!(solve_fast(&same_col).success, SolveSuccess::Unsolvable);
+    }
+
+    #[test]
+    fn test_solve_fast_matches_solve() {
+        for line in &[
+            "1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1",
+            ".7.39164...16.459769475.13.4..219876926875413817463259..91..76474.5.6921162947385",
+            "49......838..........6............3.574.6.........7..29...18.6...83...4..6.4.51.7",
+        ] {
+            let sudoku = Sudoku::from_line(line).unwrap();
+            let expected = solve(sudoku.clone(), &SolveOpts::fast());
+            let res = solve_fast(&sudoku);
+            assert_eq!(res.success, expected.success);
+            assert_eq!(res.sudoku, expected.sudoku);
+        }
+    }
+}
@@ -2,21 +2,20 @@ use crate::{
     pos::{Pos, PosBitSet},
     solver::{Row, Col, Block, Line},
 };
+use std::fmt;
 
-/// All Rows, then all Cols, then all Blocks
-pub(crate) struct House(u8);
+/// Any row, column or block. Numbered as all Rows, then all Cols, then all Blocks
+pub struct House(u8);
 
 impl_index_type!(House(u8), 27);
 impl_type_indexed_slice!(HouseIndexedSlice, House, pub(crate));
 
 impl House {
-    #[allow(dead_code)]
     #[inline]
     pub const fn from_row(row: Row) -> Self {
         Self(row.as_usize() as u8)
     }
 
-    #[allow(dead_code)]
     #[inline]
     pub const fn from_col(col: Col) -> Self {
         Self((col.as_usize() + Row::N) as u8)
@@ -32,18 +31,56 @@ impl House {
         Self((block.as_usize() + {2 * Row::N}) as u8)
     }
 
-    #[allow(dead_code)]
+    #[inline]
+    pub fn as_row(&self) -> Option<Row> {
+        if self.as_usize() < Row::N { Some(Row::new(self.as_usize())) } else { None }
+    }
+
+    #[inline]
+    pub fn as_col(&self) -> Option<Col> {
+        match self.as_usize() {
+            idx if (Row::N .. 2 * Row::N).contains(&idx) => Some(Col::new(idx - Row::N)),
+            _ => None
+        }
+    }
+
+    #[inline]
+    pub fn as_block(&self) -> Option<Block> {
+        if self.as_usize() >= 2 * Row::N { Some(Block::new(self.as_usize() - 2 * Row::N)) } else { None }
+    }
+
+    /// The row, column and block containing the position
+    #[inline]
+    pub fn containing(pos: Pos) -> [House; 3] {
+        [
+            House::from_row(Row::new(pos.row() as usize)),
+            House::from_col(Col::new(pos.col() as usize)),
+            House::from_block(Block::from_pos(pos)),
+        ]
+    }
+
     #[inline]
     pub fn members_iter(&self) -> impl Iterator<Item = Pos> {
         MEMBER_VECS[*self].iter().cloned()
     }
 
     #[inline]
-    pub fn members_bitset(&self) -> PosBitSet {
+    pub(crate) fn members_bitset(&self) -> PosBitSet {
         MEMBER_BITSETS[*self]
     }
 }
 
+impl fmt::Display for House {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.as_row(), self.as_col(), self.as_block()) {
+            (Some(row), _, _) => row.fmt(f),
+            (_, Some(col), _) => col.fmt(f),
+            (_, _, Some(block)) => block.fmt(f),
+            _ => unreachable!()
+        }
+    }
+}
+
 #[static_init::dynamic]
 static MEMBER_VECS: HouseIndexedSlice<Vec<Pos>> = {
     const EMPTY_POS_VEC: Vec<Pos> = Vec::new(); // Workaround for array initialization
@@ -71,3 +108,19 @@ static MEMBER_BITSETS: HouseIndexedSlice<PosBitSet> = {
     }
     ret
 };
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_house_kinds() {
+        for house in House::iter() {
+            let kinds = [house.as_row().is_some(), house.as_col().is_some(), house.as_block().is_some()];
+            assert_eq!(kinds.iter().filter(|&&kind| kind).count(), 1);
+        }
+        assert_eq!(House::from_row(Row::new(3)).as_row(), Some(Row::new(3)));
+        assert_eq!(House::from_col(Col::new(4)).as_col(), Some(Col::new(4)));
+        assert_eq!(House::from_block(Block::new(5)).as_block(), Some(Block::new(5)));
+    }
+}
@@ -2,9 +2,10 @@ use crate::{
     pos::{Pos, PosBitSet},
     solver::{Row, Col},
 };
+use std::fmt;
 
-/// All Rows then all Cols
-pub(crate) struct Line(u8);
+/// Either a row or a column. Numbered as all Rows then all Cols
+pub struct Line(u8);
 
 impl_index_type!(Line(u8), 18);
 impl_type_indexed_slice!(LineIndexedSlice, Line, pub(crate));
@@ -20,6 +21,16 @@ impl Line {
         Self((col.as_usize() + Row::N) as u8)
     }
 
+    #[inline]
+    pub fn as_row(&self) -> Option<Row> {
+        if self.as_usize() < Row::N { Some(Row::new(self.as_usize())) } else { None }
+    }
+
+    #[inline]
+    pub fn as_col(&self) -> Option<Col> {
+        if self.as_usize() >= Row::N { Some(Col::new(self.as_usize() - Row::N)) } else { None }
+    }
+
     #[inline]
     pub fn members_iter(&self) -> impl Iterator<Item = Pos> {
         MEMBER_VECS[*self].iter().cloned()
@@ -27,11 +38,21 @@ impl Line {
 
     #[allow(dead_code)]
     #[inline]
-    pub fn members_bitset(&self) -> PosBitSet {
+    pub(crate) fn members_bitset(&self) -> PosBitSet {
         MEMBER_BITSETS[*self]
     }
 }
 
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.as_row(), self.as_col()) {
+            (Some(row), _) => row.fmt(f),
+            (_, Some(col)) => col.fmt(f),
+            _ => unreachable!()
+        }
+    }
+}
+
 #[static_init::dynamic]
 static MEMBER_VECS: LineIndexedSlice<Vec<Pos>> = {
     const EMPTY_POS_VEC: Vec<Pos> = Vec::new(); // Workaround for array initialization
@@ -1,20 +1,29 @@
 mod block;
 mod col;
+mod fast;
 mod house;
 mod line;
+mod rating;
 mod row;
 mod solver;
+mod solutions;
 mod strategies;
 #[cfg(test)]
 mod tests;
 
-pub(crate) use block::Block;
-pub(crate) use col::Col;
-pub(crate) use house::House;
-pub(crate) use line::Line;
-pub(crate) use row::Row;
+pub use block::Block;
+pub use col::Col;
+pub use house::House;
+pub use line::Line;
+pub use row::Row;
 pub(crate) use crate::pos::{PosBitSet, PosIndexedSlice};
 pub(crate) use crate::value::{ValueBitSet, ValueIndexedSlice};
 
+pub use fast::solve_fast;
+pub use rating::{rate, step_score, Difficulty, Rating};
+pub use solutions::{count_solutions, solutions, Solutions};
 pub use solver::{solve, SolveOpts, SolveResult, SolveSuccess};
-pub use strategies::{Strategy, ALL, FAST};
+pub use strategies::{
+    AicKind, Contradiction, ForcingChainBranch, ForcingChainKind, Implication, LinkType, SingleDigitPatternKind, Strategy,
+    StrategyParseError, StrategyResult, UniqueRectangleKind, ALL, FAST, TRIAL,
+};
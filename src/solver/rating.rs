@@ -0,0 +1,220 @@
+use crate::{
+    solver::{
+        solve, ForcingChainKind, LinkType, SingleDigitPatternKind, SolveOpts, SolveSuccess, Strategy, StrategyResult,
+        UniqueRectangleKind,
+    },
+    Sudoku,
+};
+
+/// Broad difficulty categories, determined by the hardest step needed to solve a sudoku
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difficulty {
+    /// Only singles are needed
+    Easy,
+    /// Locked candidates, pairs and X-Wings
+    Medium,
+    /// Larger subsets and fish, and the simple wings
+    Hard,
+    /// Coloring and other chains
+    Expert,
+    /// Pattern overlay or guessing is needed
+    Diabolical,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rating {
+    /// The rating of the hardest step, on a scale similar to Sudoku Explainer's (1.0 - 11.0)
+    pub score: f32,
+    /// The sum of the ratings of every step, useful to order sudokus with the same `score`
+    pub total_score: f32,
+    pub difficulty: Difficulty,
+    /// The strategy used in the hardest step, or `None` if guess and check was needed
+    pub hardest_strategy: Option<Strategy>,
+    /// How many times each strategy was used, in the order they were first used
+    pub strategy_counts: Vec<(Strategy, usize)>,
+    /// How many times we had to guess and check
+    pub guesses: usize,
+    pub success: SolveSuccess,
+}
+
+const GUESS_SCORE: f32 = 10.0;
+
+/// The rating of a single step, loosely following the Sudoku Explainer scale where it has an
+/// equivalent technique.
+pub fn step_score(step: &StrategyResult) -> f32 {
+    match step {
+        StrategyResult::HiddenSingle(_, _, house) => if house.as_block().is_some() { 1.2 } else { 1.5 },
+        StrategyResult::NakedSingle(_, _) => 2.3,
+        StrategyResult::LockedCandidate { pointing, .. } => if *pointing { 2.6 } else { 2.8 },
+        StrategyResult::NakedSubset { positions, .. } => match positions.len() {
+            2 => 3.0,
+            3 => 3.6,
+            _ => 5.0,
+        },
+        StrategyResult::HiddenSubset { values, .. } => match values.len() {
+            2 => 3.4,
+            3 => 4.0,
+            _ => 5.4,
+        },
+        StrategyResult::Fish { base_lines, .. } => fish_score(base_lines.len()),
+        // Sashimi fish are harder to spot than finned ones, as they don't look like a fish
+        StrategyResult::FinnedFish { base_lines, sashimi, .. } => {
+            let score = fish_score(base_lines.len()) + 0.2;
+            if *sashimi { score + 0.2 } else { score }
+        },
+        // A skyscraper is a sashimi X-Wing, and the others are similar short chains
+        StrategyResult::SingleDigitPattern { kind, .. } => match kind {
+            SingleDigitPatternKind::Skyscraper => 3.6,
+            SingleDigitPatternKind::TwoStringKite => 3.7,
+            SingleDigitPatternKind::TurbotFish => 3.8,
+        },
+        StrategyResult::EmptyRectangle { .. } => 3.8,
+        // Blocks make the fish much harder to see, and mixing rows and columns harder still
+        StrategyResult::ComplexFish { base_houses, mutant, .. } =>
+            fish_score(base_houses.len()) + if *mutant { 1.0 } else { 0.6 },
+        StrategyResult::XyWing { .. } => 4.2,
+        StrategyResult::XyzWing { .. } => 4.4,
+        StrategyResult::WxyzWing { .. } => 4.6,
+        // Matching the XYZ and WXYZ-Wings for three and four cells
+        StrategyResult::Wing { positions, .. } => 3.8 + 0.2 * positions.len() as f32,
+        StrategyResult::WWing { .. } => 4.1,
+        StrategyResult::RemotePair { .. } => 3.5,
+        StrategyResult::SueDeCoq { .. } => 5.0,
+        StrategyResult::JuniorExocet { .. } => 7.5,
+        StrategyResult::UniqueRectangle { kind, .. } => match kind {
+            UniqueRectangleKind::Type1 | UniqueRectangleKind::Type2 => 4.5,
+            UniqueRectangleKind::Type3 | UniqueRectangleKind::Type4 => 4.6,
+            UniqueRectangleKind::Type5 | UniqueRectangleKind::Type6 => 4.7,
+            UniqueRectangleKind::Hidden => 4.8,
+            UniqueRectangleKind::Avoidable1 => 4.7,
+            UniqueRectangleKind::Avoidable2 => 4.5,
+        },
+        // Each extra candidate adds to what has to be checked
+        StrategyResult::BivalueUniversalGrave { extra_candidates, .. } =>
+            (5.5 + 0.1 * extra_candidates.len() as f32).min(6.2),
+        StrategyResult::SimpleColor { .. } => 6.5,
+        // Longer chains are harder to spot
+        StrategyResult::MultiColor { color_positions, .. } => (6.4 + 0.1 * color_positions.len() as f32).min(7.0),
+        StrategyResult::XChain { chain, .. } | StrategyResult::XyChain { chain, .. } => chain_score(chain.len()),
+        // Mixing links of different kinds makes the chain harder to follow, and groups and almost
+        // locked sets harder still
+        StrategyResult::Aic { chain, .. } => {
+            let grouped = chain.iter().any(|(positions, _, _)| positions.len() > 1);
+            let als = chain.iter().any(|&(_, _, link_type)| link_type == LinkType::Als);
+            chain_score(chain.len()) + 0.2 + if als { 0.4 } else if grouped { 0.2 } else { 0.0 }
+        },
+        StrategyResult::AlsXz { restricted_commons, .. } => if restricted_commons.len() > 1 { 6.8 } else { 6.6 },
+        StrategyResult::AlsXyWing { .. } => 7.0,
+        // Each petal is another set to find
+        StrategyResult::DeathBlossom { petals, .. } => (6.9 + 0.1 * petals.len() as f32).min(7.5),
+        StrategyResult::PatternOverlay { .. } => 8.0,
+        // Longer trials are harder to follow
+        StrategyResult::Trial { steps, .. } => (7.5 + 0.1 * steps.len() as f32).min(8.5),
+        StrategyResult::ForcingChain { kind, .. } => match kind {
+            ForcingChainKind::Cell => 8.2,
+            ForcingChainKind::Region(_) => 8.4,
+            ForcingChainKind::Digit => 8.6,
+        },
+        StrategyResult::GuessAndCheck(_, _) => GUESS_SCORE,
+    }
+}
+
+fn fish_score(size: usize) -> f32 {
+    match size {
+        2 => 3.2,
+        3 => 3.8,
+        _ => 5.2,
+    }
+}
+
+/// Chains are a little harder than coloring, and get harder with each strong link
+fn chain_score(nodes: usize) -> f32 {
+    (6.4 + 0.1 * (nodes / 2) as f32).min(7.0)
+}
+
+fn difficulty_for_score(score: f32) -> Difficulty {
+    if score <= 2.3 {
+        Difficulty::Easy
+    } else if score <= 3.4 {
+        Difficulty::Medium
+    } else if score <= 5.0 {
+        Difficulty::Hard
+    } else if score <= 7.0 {
+        Difficulty::Expert
+    } else {
+        Difficulty::Diabolical
+    }
+}
+
+/// Rates how difficult a sudoku is to solve, by solving it with all strategies (see
+/// [`crate::solver::ALL`]), and looking at the steps that were needed. Strategies which assume
+/// the sudoku is unique aren't used, as it may not be.
+pub fn rate(sudoku: &Sudoku) -> Rating {
+    let res = solve(sudoku.clone(), &SolveOpts::default());
+
+    let mut rating = Rating {
+        score: 0.0,
+        total_score: 0.0,
+        difficulty: Difficulty::Easy,
+        hardest_strategy: None,
+        strategy_counts: Vec::new(),
+        guesses: 0,
+        success: res.success,
+    };
+    for step in &res.steps {
+        let score = step_score(step);
+        if score > rating.score {
+            rating.score = score;
+            rating.hardest_strategy = step.strategy();
+        }
+        rating.total_score += score;
+        match step.strategy() {
+            Some(strat) => match rating.strategy_counts.iter_mut().find(|(strat2, _)| *strat2 == strat) {
+                Some((_, cnt)) => *cnt += 1,
+                None => rating.strategy_counts.push((strat, 1)),
+            },
+            None => rating.guesses += 1,
+        }
+    }
+    rating.difficulty = difficulty_for_score(rating.score);
+    rating
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_easy() {
+        let sudoku = Sudoku::from_line(".9.3....7..4.....58.7..6...6......2..7..9......5....76......5...4.28.3....3.5..8.").unwrap();
+        let rating = rate(&sudoku);
+        assert_eq!(rating.success, SolveSuccess::Unique);
+        assert_eq!(rating.guesses, 0);
+        assert!(rating.score <= 2.3);
+        assert_eq!(rating.difficulty, Difficulty::Easy);
+        assert!(rating.strategy_counts.iter().all(|&(strat, _)| matches!(strat, Strategy::NakedSingle | Strategy::HiddenSingle)));
+    }
+
+    #[test]
+    fn test_rate_hardest_step() {
+        let sudoku = Sudoku::from_line("9812763542639541..574183692315..8..9428..95..796..5...642597813157832946839641725").unwrap();
+        let rating = rate(&sudoku);
+        assert_eq!(rating.success, SolveSuccess::Unique);
+        assert_eq!(rating.hardest_strategy, Some(Strategy::XyWing));
+        assert_eq!(rating.score, 4.2);
+        assert_eq!(rating.difficulty, Difficulty::Hard);
+        assert!(rating.strategy_counts.contains(&(Strategy::XyWing, 1)));
+        assert!(rating.total_score > rating.score);
+    }
+
+    #[test]
+    fn test_rate_guess() {
+        let sudoku = Sudoku::from_line("1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1").unwrap();
+        let rating = rate(&sudoku);
+        assert_eq!(rating.success, SolveSuccess::Unique);
+        assert!(rating.guesses > 0);
+        assert_eq!(rating.score, GUESS_SCORE);
+        assert_eq!(rating.hardest_strategy, None);
+        assert_eq!(rating.difficulty, Difficulty::Diabolical);
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{
     pos::{Pos, PosBitSet},
 };
+use std::fmt;
 
-pub(crate) struct Row(u8);
+pub struct Row(u8);
 
 impl_index_type!(Row(u8), 9);
 impl_type_indexed_slice!(RowIndexedSlice, Row, pub(crate));
@@ -20,11 +21,17 @@ impl Row {
 
     #[allow(dead_code)]
     #[inline]
-    pub fn members_bitset(&self) -> PosBitSet {
+    pub(crate) fn members_bitset(&self) -> PosBitSet {
         MEMBER_BITSETS[*self]
     }
 }
 
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "row {}", self.0 + 1)
+    }
+}
+
 #[static_init::dynamic]
 static MEMBER_VECS: RowIndexedSlice<Vec<Pos>> = {
     const EMPTY_POS_VEC: Vec<Pos> = Vec::new(); // Workaround for array initialization
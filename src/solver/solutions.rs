@@ -0,0 +1,88 @@
+use crate::{
+    solver::{solve, strategies, SolveOpts},
+    Pos, Sudoku,
+};
+
+/// An iterator over every solution of a sudoku, see [`solutions`].
+pub struct Solutions {
+    /// Partially solved sudokus still to be explored, the last is explored first
+    stack: Vec<Sudoku>,
+}
+
+const NO_GUESS_OPTS: SolveOpts<'static> = SolveOpts {
+    strategies: strategies::FAST,
+    guess_and_check: false,
+    stop_after_first_step: false,
+    uniqueness_strategies: false,
+};
+
+impl Iterator for Solutions {
+    type Item = Sudoku;
+
+    fn next(&mut self) -> Option<Sudoku> {
+        while let Some(sudoku) = self.stack.pop() {
+            let res = solve(sudoku, &NO_GUESS_OPTS);
+            if res.is_unique() { return Some(res.sudoku) }
+            let sudoku = res.sudoku;
+            if !sudoku.progress_possible() { continue } // Stuck without being solved, a dead end
+
+            // Same as guess and check, but we keep every branch around instead of stopping at the
+            // second solution. A cell with no candidates left is a dead end with no branches.
+            let pos = Pos::iter()
+                .filter(|&pos| sudoku.get_value(pos).is_none())
+                .min_by_key(|&pos| sudoku.get_candidates_by_pos(pos).len())
+                .unwrap();
+            let candidates: Vec<_> = sudoku.get_candidates_by_pos(pos).iter().collect();
+            for &val in candidates.iter().rev() {
+                let mut sudoku2 = sudoku.clone();
+                sudoku2.set_value(pos, val);
+                self.stack.push(sudoku2);
+            }
+        }
+        None
+    }
+}
+
+/// Returns an iterator which yields every solution of the sudoku. Beware that sudokus with few
+/// givens can have an enormous number of solutions.
+pub fn solutions(sudoku: &Sudoku) -> Solutions {
+    Solutions { stack: vec![sudoku.clone()] }
+}
+
+/// Counts the solutions of the sudoku, stopping once `limit` solutions are found.
+pub fn count_solutions(sudoku: &Sudoku, limit: usize) -> usize {
+    solutions(sudoku).take(limit).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_solutions() {
+        let unique = Sudoku::from_line("4...3.......6..8..........1....5..9..8....6...7.2........1.27..5.3....4.9........").unwrap();
+        assert_eq!(count_solutions(&unique, usize::MAX), 1);
+
+        // Deadly pattern in r1c5,r1c8,r2c5,r2c8
+        let two = Sudoku::from_line("4689.15.77516.48.9392578461134756298289413675675289314846192753513867942927345186").unwrap();
+        assert_eq!(count_solutions(&two, usize::MAX), 2);
+        assert_eq!(count_solutions(&two, 1), 1);
+
+        let none = Sudoku::from_line("12345678.........9...............................................................").unwrap();
+        assert_eq!(count_solutions(&none, usize::MAX), 0);
+
+        let empty = Sudoku::new();
+        assert_eq!(count_solutions(&empty, 100), 100);
+    }
+
+    #[test]
+    fn test_solutions() {
+        let two = Sudoku::from_line("4689.15.77516.48.9392578461134756298289413675675289314846192753513867942927345186").unwrap();
+        let all: Vec<_> = solutions(&two).collect();
+        assert_eq!(all.len(), 2);
+        assert_ne!(all[0], all[1]);
+        for sudoku in all {
+            assert!(sudoku.is_solved());
+        }
+    }
+}
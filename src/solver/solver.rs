@@ -1,14 +1,15 @@
 use crate::{
     solver::{
         strategies::{
-            self, Coloring, Strategy, StrategyResult, KnownSubsets,
+            self, AlmostLockedSet, Coloring, Strategy, StrategyResult, KnownSubsets,
         },
         house::HouseIndexedSlice,
-        House, ValueIndexedSlice,
+        House, PosBitSet, ValueIndexedSlice,
     },
-    Sudoku, Value,
+    Pos, Sudoku, Value,
 };
 
+#[derive(Copy, Clone)]
 pub struct SolveOpts<'a> {
     /// Strategies to try when solving, in order
     pub strategies: &'a [Strategy],
@@ -16,6 +17,11 @@ pub struct SolveOpts<'a> {
     pub guess_and_check: bool,
     /// Stop trying to solve further after the first step is made
     pub stop_after_first_step: bool,
+    /// Whether to use strategies which assume the sudoku has a unique solution (see
+    /// [`Strategy::requires_uniqueness`]). These are off by default, and should only be enabled
+    /// when the sudoku is known to be unique, as otherwise they can remove solutions until only
+    /// one is left, so a non-unique sudoku is reported as unique.
+    pub uniqueness_strategies: bool,
 }
 
 impl Default for SolveOpts<'_> {
@@ -24,6 +30,7 @@ impl Default for SolveOpts<'_> {
             strategies: &strategies::ALL,
             guess_and_check: true,
             stop_after_first_step: false,
+            uniqueness_strategies: false,
         }
     }
 }
@@ -34,6 +41,7 @@ impl SolveOpts<'_> {
             strategies: &strategies::FAST,
             guess_and_check: true,
             stop_after_first_step: false,
+            uniqueness_strategies: false,
         }
     }
 }
@@ -51,7 +59,8 @@ pub struct SolveResult {
     /// If the sudoku was solvable, one of those solutions, otherwise the sudoku as far as we could
     /// solve it.
     pub sudoku: Sudoku,
-    pub(crate) steps: Vec<StrategyResult> // TODO how to expose House, etc?
+    /// The steps taken while solving, in order
+    pub steps: Vec<StrategyResult>
 }
 
 impl SolveResult {
@@ -85,31 +94,67 @@ impl SolveResult {
     }
 }
 
-fn run_strategies(sudoku: &Sudoku, opts: &SolveOpts) -> Option<StrategyResult> {
+/// `givens` are the cells which were solved before we started, as some uniqueness strategies
+/// depend on which cells could be swapped
+pub(crate) fn run_strategies(sudoku: &Sudoku, givens: PosBitSet, opts: &SolveOpts) -> Option<StrategyResult> {
     struct TmpSolveState {
         known_subsets: HouseIndexedSlice<KnownSubsets>,
-        colorings: ValueIndexedSlice<Option<Coloring>>
+        colorings: ValueIndexedSlice<Option<Coloring>>,
+        almost_locked_sets: Option<Vec<AlmostLockedSet>>
     }
     const NONE_COLORING: Option<Coloring> = None;
     let mut tmp_solve_state = TmpSolveState {
         known_subsets: HouseIndexedSlice::from_slice([Default::default(); House::N]),
         colorings: ValueIndexedSlice::from_slice([NONE_COLORING; Value::N]),
+        almost_locked_sets: None,
     };
     for strat in opts.strategies {
+        if !opts.uniqueness_strategies && strat.requires_uniqueness() { continue }
         let res = match strat {
+            Strategy::Aic { max_length, grouped, als } => strategies::aic(&sudoku, *max_length, *grouped, *als),
+            Strategy::AlsXz => strategies::als_xz(&sudoku, &mut tmp_solve_state.almost_locked_sets),
+            Strategy::AlsXyWing => strategies::als_xy_wing(&sudoku, &mut tmp_solve_state.almost_locked_sets),
+            Strategy::AvoidableRectangle => strategies::avoidable_rectangle(&sudoku, givens),
+            Strategy::BivalueUniversalGrave => strategies::bivalue_universal_grave(&sudoku),
+            Strategy::CellForcingChain => strategies::cell_forcing_chain(&sudoku),
+            Strategy::DeathBlossom => strategies::death_blossom(&sudoku, &mut tmp_solve_state.almost_locked_sets),
+            Strategy::DigitForcingChain => strategies::digit_forcing_chain(&sudoku),
+            Strategy::EmptyRectangle => strategies::empty_rectangle(&sudoku),
+            Strategy::FinnedJellyfish => strategies::finned_jellyfish(&sudoku),
+            Strategy::FinnedSwordfish => strategies::finned_swordfish(&sudoku),
+            Strategy::FinnedXWing => strategies::finned_x_wing(&sudoku),
+            Strategy::FrankenFish(max_size) => strategies::franken_fish(&sudoku, *max_size),
             Strategy::HiddenPair => strategies::hidden_pair(&sudoku, &mut tmp_solve_state.known_subsets),
             Strategy::HiddenQuadruple => strategies::hidden_quadruple(&sudoku, &mut tmp_solve_state.known_subsets),
             Strategy::HiddenSingle => strategies::hidden_single(&sudoku),
             Strategy::HiddenTriple => strategies::hidden_triple(&sudoku, &mut tmp_solve_state.known_subsets),
+            Strategy::HiddenUniqueRectangle => strategies::hidden_unique_rectangle(&sudoku),
+            Strategy::Jellyfish => strategies::jellyfish(&sudoku),
+            Strategy::JuniorExocet => strategies::junior_exocet(&sudoku),
             Strategy::LockedCandidate => strategies::locked_candidate(&sudoku),
             Strategy::MultiColor(max_color_pairs) => strategies::multi_color(&sudoku, *max_color_pairs, &mut tmp_solve_state.colorings),
+            Strategy::MutantFish(max_size) => strategies::mutant_fish(&sudoku, *max_size),
             Strategy::NakedPair => strategies::naked_pair(&sudoku, &mut tmp_solve_state.known_subsets),
             Strategy::NakedQuadruple => strategies::naked_quadruple(&sudoku, &mut tmp_solve_state.known_subsets),
             Strategy::NakedSingle => strategies::naked_single(&sudoku),
             Strategy::NakedTriple => strategies::naked_triple(&sudoku, &mut tmp_solve_state.known_subsets),
             Strategy::PatternOverlay => strategies::pattern_overlay(&sudoku),
+            Strategy::RegionForcingChain => strategies::region_forcing_chain(&sudoku),
+            Strategy::RemotePair => strategies::remote_pair(&sudoku),
             Strategy::SimpleColor => strategies::simple_color(&sudoku, &mut tmp_solve_state.colorings),
+            Strategy::Skyscraper => strategies::skyscraper(&sudoku),
+            Strategy::SueDeCoq => strategies::sue_de_coq(&sudoku),
+            Strategy::Swordfish => strategies::swordfish(&sudoku),
+            Strategy::Trial { max_steps, strategies } => strategies::trial(&sudoku, *max_steps, strategies),
+            Strategy::TurbotFish => strategies::turbot_fish(&sudoku),
+            Strategy::TwoStringKite => strategies::two_string_kite(&sudoku),
+            Strategy::UniqueRectangle => strategies::unique_rectangle(&sudoku),
+            Strategy::Wing(max_size) => strategies::wing(&sudoku, *max_size),
+            Strategy::WWing => strategies::w_wing(&sudoku),
             Strategy::WxyzWing => strategies::wxyz_wing(&sudoku),
+            Strategy::XChain(max_nodes) => strategies::x_chain(&sudoku, *max_nodes),
+            Strategy::XWing => strategies::x_wing(&sudoku),
+            Strategy::XyChain(max_nodes) => strategies::xy_chain(&sudoku, *max_nodes),
             Strategy::XyWing => strategies::xy_wing(&sudoku),
             Strategy::XyzWing => strategies::xyz_wing(&sudoku),
         };
@@ -120,8 +165,9 @@ fn run_strategies(sudoku: &Sudoku, opts: &SolveOpts) -> Option<StrategyResult> {
 
 pub fn solve(mut sudoku: Sudoku, opts: &SolveOpts) -> SolveResult {
     let mut steps = Vec::new();
+    let givens: PosBitSet = Pos::iter().filter(|&pos| sudoku.get_value(pos).is_some()).collect();
     while sudoku.progress_possible() {
-        match run_strategies(&sudoku, &opts) {
+        match run_strategies(&sudoku, givens, &opts) {
             None => break, // No further progress unless we guess and check
             Some(res) => {
                 for (pos, val) in res.excluded_candidates() {
@@ -168,4 +214,14 @@ mod tests {
         let solve_res = solve(sudoku, &SolveOpts::fast());
         assert!(matches!(solve_res.success, SolveSuccess::NonUnique));
     }
+
+    #[test]
+    fn test_solve_non_unique_without_uniqueness_strategies() {
+        // Unique rectangles remove all but one of the solutions of this sudoku, so it is only
+        // reported correctly when they aren't used
+        let line = "......3.8..6......31...8....7165...2..4..1.5.....4.1...6.....81.....3..94..2.....";
+        let sudoku = Sudoku::from_line(line).unwrap();
+        assert!(solve(sudoku.clone(), &SolveOpts::default()).is_non_unique());
+        assert!(solve(sudoku, &SolveOpts::fast()).is_non_unique());
+    }
 }
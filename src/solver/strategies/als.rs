@@ -0,0 +1,371 @@
+use super::StrategyResult;
+use crate::{
+    solver::{House, PosBitSet, ValueBitSet},
+    Pos, Sudoku, Value,
+};
+use std::collections::HashSet;
+
+/// The largest almost locked sets to look for. A whole house minus one cell is the largest there
+/// can be.
+const MAX_SIZE: usize = Value::N - 1;
+
+/// An almost locked set: `n` cells in one house with `n + 1` candidates between them. If any one
+/// of the candidates is removed from the set, the rest are locked into its cells.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AlmostLockedSet {
+    pub positions: PosBitSet,
+    pub values: ValueBitSet,
+}
+
+impl AlmostLockedSet {
+    /// The cells of the set with the value as a candidate
+    #[inline]
+    pub fn positions_of(&self, sudoku: &Sudoku, val: Value) -> PosBitSet {
+        self.positions & sudoku.get_candidates_by_value(val)
+    }
+}
+
+/// Every almost locked set of at most `max_size` cells. Sets in the intersection of a block and a
+/// line are only returned once.
+pub(crate) fn almost_locked_sets(sudoku: &Sudoku, max_size: usize) -> Vec<AlmostLockedSet> {
+    let mut ret = Vec::new();
+    let mut seen = HashSet::new();
+    for house in House::iter() {
+        let positions: Vec<Pos> = house.members_iter()
+            .filter(|&pos| !sudoku.get_candidates_by_pos(pos).is_empty())
+            .collect();
+        add_almost_locked_sets(sudoku, &positions, max_size, PosBitSet::NONE, ValueBitSet::NONE, &mut seen, &mut ret);
+    }
+    ret
+}
+
+fn add_almost_locked_sets(sudoku: &Sudoku, positions: &[Pos], max_size: usize, set_positions: PosBitSet,
+    values: ValueBitSet, seen: &mut HashSet<PosBitSet>, ret: &mut Vec<AlmostLockedSet>)
+{
+    for (idx, &pos) in positions.iter().enumerate() {
+        let mut set_positions = set_positions;
+        set_positions.insert(pos);
+        let values = values | sudoku.get_candidates_by_pos(pos);
+        // Each extra cell adds at most one to the difference between the number of cells and
+        // values, so there's no way to get down to n + 1 values
+        if values.len() > max_size + 1 { continue }
+
+        let size = set_positions.len();
+        if values.len() == size + 1 && seen.insert(set_positions) {
+            ret.push(AlmostLockedSet { positions: set_positions, values });
+        }
+        if size < max_size {
+            add_almost_locked_sets(sudoku, &positions[idx + 1 ..], max_size, set_positions, values, seen, ret);
+        }
+    }
+}
+
+/// The almost locked sets used by the ALS strategies, found by the first of them to run on the
+/// sudoku and reused by the rest
+fn cached_sets<'a>(sudoku: &Sudoku, sets: &'a mut Option<Vec<AlmostLockedSet>>) -> &'a [AlmostLockedSet] {
+    sets.get_or_insert_with(|| almost_locked_sets(sudoku, MAX_SIZE))
+}
+
+/// The positions which see all of the positions
+pub(crate) fn common_neighbors(positions: PosBitSet) -> PosBitSet {
+    positions.iter().fold(PosBitSet::ALL, |neighbors, pos| neighbors & pos.neighbors_bitset())
+}
+
+/// The candidates of the value which see every cell of the positions with the value, so are
+/// excluded if the value must be in one of those cells
+fn excluded_by(sudoku: &Sudoku, positions: PosBitSet, val: Value) -> impl Iterator<Item = (Pos, Value)> {
+    let candidates = sudoku.get_candidates_by_value(val);
+    let neighbors = if (positions & candidates).is_empty() { PosBitSet::NONE } else { common_neighbors(positions & candidates) };
+    (neighbors & candidates).iter().map(move |pos| (pos, val))
+}
+
+/// The restricted common candidates of two sets: values which can only be in one of them, as every
+/// cell with the value in one set sees every cell with it in the other
+fn restricted_commons(sudoku: &Sudoku, als1: &AlmostLockedSet, als2: &AlmostLockedSet) -> ValueBitSet {
+    if !(als1.positions & als2.positions).is_empty() { return ValueBitSet::NONE }
+    (als1.values & als2.values).iter()
+        .filter(|&val| als2.positions_of(sudoku, val).difference(common_neighbors(als1.positions_of(sudoku, val))).is_empty())
+        .collect()
+}
+
+fn sorted(mut excluded_candidates: Vec<(Pos, Value)>) -> Vec<(Pos, Value)> {
+    excluded_candidates.sort_unstable();
+    excluded_candidates.dedup();
+    excluded_candidates
+}
+
+/// Two almost locked sets with a restricted common candidate X. X can only be in one of them, so
+/// the other is locked, and any other common value Z must be in one of the sets. With two
+/// restricted common candidates, both sets are locked.
+pub(crate) fn als_xz(sudoku: &Sudoku, sets: &mut Option<Vec<AlmostLockedSet>>) -> Option<StrategyResult> {
+    let sets = cached_sets(sudoku, sets);
+    for (idx, als1) in sets.iter().enumerate() {
+        for als2 in &sets[idx + 1 ..] {
+            let restricted_commons = restricted_commons(sudoku, als1, als2);
+            if restricted_commons.is_empty() { continue }
+
+            let positions = als1.positions | als2.positions;
+            let mut excluded_candidates = Vec::new();
+            if restricted_commons.len() == 1 {
+                for val in (als1.values & als2.values).difference(restricted_commons).iter() {
+                    excluded_candidates.extend(excluded_by(sudoku, positions, val));
+                }
+            } else {
+                // Each restricted common candidate is in one of the sets, and the rest of the
+                // values are locked in their own set
+                for val in restricted_commons.iter() {
+                    excluded_candidates.extend(excluded_by(sudoku, positions, val));
+                }
+                for als in &[als1, als2] {
+                    for val in als.values.difference(restricted_commons).iter() {
+                        excluded_candidates.extend(excluded_by(sudoku, als.positions, val));
+                    }
+                }
+            }
+
+            if !excluded_candidates.is_empty() {
+                return Some(StrategyResult::AlsXz {
+                    excluded_candidates: sorted(excluded_candidates),
+                    sets: [als1.positions.iter().collect(), als2.positions.iter().collect()],
+                    restricted_commons: restricted_commons.iter().collect(),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Two almost locked sets A and B, with restricted common candidates X and Y to a third set C.
+/// C can't be missing both X and Y, so one of A or B is locked, and any value Z common to both
+/// must be in one of them.
+pub(crate) fn als_xy_wing(sudoku: &Sudoku, sets: &mut Option<Vec<AlmostLockedSet>>) -> Option<StrategyResult> {
+    let sets = cached_sets(sudoku, sets);
+    for als3 in sets {
+        let linked: Vec<(&AlmostLockedSet, ValueBitSet)> = sets.iter()
+            .map(|als| (als, restricted_commons(sudoku, als, als3)))
+            .filter(|(_, restricted_commons)| !restricted_commons.is_empty())
+            .collect();
+
+        for (idx, &(als1, restricted_commons1)) in linked.iter().enumerate() {
+            for &(als2, restricted_commons2) in &linked[idx + 1 ..] {
+                if !(als1.positions & als2.positions).is_empty() { continue }
+                let common_values = als1.values & als2.values;
+                for x in restricted_commons1.iter() {
+                    for y in restricted_commons2.iter().filter(|&y| y != x) {
+                        let mut excluded_candidates = Vec::new();
+                        for z in common_values.iter().filter(|&z| z != x && z != y) {
+                            excluded_candidates.extend(excluded_by(sudoku, als1.positions | als2.positions, z));
+                        }
+
+                        if !excluded_candidates.is_empty() {
+                            return Some(StrategyResult::AlsXyWing {
+                                excluded_candidates: sorted(excluded_candidates),
+                                sets: [als1.positions.iter().collect(), als2.positions.iter().collect(), als3.positions.iter().collect()],
+                                restricted_commons: [x, y],
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A stem cell, and for each of its candidates an almost locked set (a petal) where every cell
+/// with that value sees the stem. Whichever value the stem has locks its petal, so any value Z
+/// in every petal but not the stem must be in one of the petals.
+pub(crate) fn death_blossom(sudoku: &Sudoku, sets: &mut Option<Vec<AlmostLockedSet>>) -> Option<StrategyResult> {
+    let sets = cached_sets(sudoku, sets);
+    for stem in Pos::iter() {
+        let stem_values = sudoku.get_candidates_by_pos(stem);
+        if stem_values.len() < 2 { continue }
+
+        let petals: Vec<(Value, Vec<&AlmostLockedSet>)> = stem_values.iter()
+            .map(|val| {
+                let petals = sets.iter()
+                    .filter(|als| !als.positions.contains(stem) && als.values.contains(val))
+                    .filter(|als| als.positions_of(sudoku, val).difference(stem.neighbors_bitset()).is_empty())
+                    .collect();
+                (val, petals)
+            })
+            .collect();
+        if petals.iter().any(|(_, petals)| petals.is_empty()) { continue }
+
+        for z in Value::iter().filter(|&z| !stem_values.contains(z)) {
+            let mut chosen = Vec::new();
+            let targets = sudoku.get_candidates_by_value(z);
+            if let Some(targets) = choose_petals(sudoku, &petals, z, PosBitSet::NONE, targets, &mut chosen) {
+                return Some(StrategyResult::DeathBlossom {
+                    excluded_candidates: targets.iter().map(|pos| (pos, z)).collect(),
+                    stem,
+                    petals: petals.iter().zip(&chosen).map(|(&(val, _), als)| (val, als.positions.iter().collect())).collect(),
+                });
+            }
+        }
+    }
+    None
+}
+
+/// Chooses a petal containing `z` for each of the remaining stem values, without overlapping the
+/// `used` cells, returning the candidates of `z` seeing every `z` in the petals
+fn choose_petals<'a>(sudoku: &Sudoku, petals: &[(Value, Vec<&'a AlmostLockedSet>)], z: Value, used: PosBitSet,
+    targets: PosBitSet, chosen: &mut Vec<&'a AlmostLockedSet>) -> Option<PosBitSet>
+{
+    let (_, options) = match petals.get(chosen.len()) {
+        Some(petal) => petal,
+        None => return Some(targets),
+    };
+    for &als in options {
+        if !als.values.contains(z) || !(als.positions & used).is_empty() { continue }
+        let targets = targets & common_neighbors(als.positions_of(sudoku, z));
+        if targets.is_empty() { continue }
+
+        chosen.push(als);
+        if let res@Some(_) = choose_petals(sudoku, petals, z, used | als.positions, targets, chosen) {
+            return res;
+        }
+        chosen.pop();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_almost_locked_sets() {
+        let sudoku = Sudoku::from_line("6.9512......349.5...3786.299..865.....549..8...412.9.579.63154...6.5...7..1.7...3").unwrap();
+        let sets = almost_locked_sets(&sudoku, 8);
+        assert!(!sets.is_empty());
+        for (idx, als) in sets.iter().enumerate() {
+            let values = als.positions.iter().fold(ValueBitSet::NONE, |values, pos| values | sudoku.get_candidates_by_pos(pos));
+            assert_eq!(values, als.values);
+            assert_eq!(als.values.len(), als.positions.len() + 1);
+            assert!(House::iter().any(|house| als.positions.difference(house.members_bitset()).is_empty()));
+            assert!(sets[idx + 1 ..].iter().all(|als2| als2.positions != als.positions));
+        }
+        // Bivalue cells are the smallest sets
+        assert!(sets.iter().any(|als| als.positions.len() == 1));
+        assert!(almost_locked_sets(&sudoku, 2).iter().all(|als| als.positions.len() <= 2));
+    }
+
+    const ALS_XZ_EXAMPLE1: &str = "\
+3    278   1    | 4    56   257  | 267   9     678
+9    6     247  | 12   8    3    | 1247  24    5
+27   2478  5    | 126  69   279  | 3     248   168
+----------------+----------------+----------------
+4    3     2689 | 268  569  1259 | 156   7     168
+5    179   6789 | 368  4    19   | 16    38    2
+26   12    268  | 236  7    125  | 9     3458  48
+----------------+----------------+----------------
+267  27    267  | 5    3    4    | 8     1     9
+1    5     3    | 9    2    8    | 47    6     47
+8    49    49   | 7    1    6    | 25    25    3";
+
+    const DOUBLY_LINKED_ALS_XZ_EXAMPLE1: &str = "\
+145689  14569  169  | 2    7     1469  | 3     45    48
+234     47     27   | 5    8     34    | 6     9     1
+134568  13456  169  | 134  39    13469 | 2478  257   248
+--------------------+------------------+----------------
+4569    45679  8    | 37   1     237   | 247   2467  349
+146     2      167  | 9    35    3578  | 478   1467  348
+19      179    3    | 6    4     278   | 5     127   289
+--------------------+------------------+----------------
+1269    8      1269 | 147  259   4579  | 249   3     56
+7       1369   4    | 13   2359  1359  | 29    8     56
+239     39     5    | 8    6     349   | 1     24    7";
+
+    const ALS_XY_WING_EXAMPLE1: &str = "\
+458    7       158   | 9    3     1458 | 2     146    168
+34568  14568   2     | 158  48    1458 | 348   9      7
+3489   1489    138   | 2    7     6    | 348   134    5
+---------------------+-----------------+------------------
+3589   589     3578  | 6    1     2789 | 3579  2357   4
+45689  145689  15678 | 3    28    2789 | 579   1257   129
+39     2       137   | 4    5     79   | 6     8      139
+---------------------+-----------------+------------------
+27     3       4     | 158  68    1258 | 5789  567    2689
+27     568     9     | 58   2468  3    | 1     4567   268
+1      568     568   | 7    9     245  | 3458  23456  2368";
+
+    const DEATH_BLOSSOM_EXAMPLE1: &str = "\
+1    7    34  | 234   245  6   | 8    25   9
+5    26   68  | 278   9    78  | 1    3    4
+348  248  9   | 1     245  38  | 67   256  567
+--------------+----------------+--------------
+349  349  1   | 45    6    2   | 59   7    8
+47   5    2   | 9     8    47  | 36   1    36
+789  69   678 | 57    3    1   | 59   4    2
+--------------+----------------+--------------
+6    1    5   | 2348  24   348 | 347  9    37
+379  349  47  | 346   1    59  | 2    8    356
+2    389  348 | 346   7    59  | 346  56   1";
+
+    #[test]
+    fn test_als_xz_example1() {
+        let sudoku = Sudoku::from_pencilmarks(ALS_XZ_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::AlsXz {
+            excluded_candidates: vec![(Pos::new(19), Value::new(6))],
+            sets: [
+                vec![Pos::new(11), Pos::new(12), Pos::new(16)],
+                vec![Pos::new(18), Pos::new(21), Pos::new(22), Pos::new(23)],
+            ],
+            restricted_commons: vec![Value::new(0)],
+        };
+        assert_eq!(step_res.to_string(), "ALS-XZ: A=r2c3,r2c4,r2c8 B=r3c1,r3c4,r3c5,r3c6 X=1 => r3c2<>7");
+        assert_eq!(als_xz(&sudoku, &mut None), Some(step_res));
+    }
+
+    #[test]
+    fn test_doubly_linked_als_xz_example1() {
+        let sudoku = Sudoku::from_pencilmarks(DOUBLY_LINKED_ALS_XZ_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::AlsXz {
+            excluded_candidates: vec![
+                (Pos::new(5), Value::new(3)), (Pos::new(18), Value::new(0)), (Pos::new(18), Value::new(5)),
+                (Pos::new(19), Value::new(0)), (Pos::new(19), Value::new(5)),
+            ],
+            sets: [vec![Pos::new(14)], vec![Pos::new(20), Pos::new(21), Pos::new(22), Pos::new(23)]],
+            restricted_commons: vec![Value::new(2), Value::new(3)],
+        };
+        assert_eq!(step_res.to_string(),
+            "Doubly Linked ALS-XZ: A=r2c6 B=r3c3,r3c4,r3c5,r3c6 X=3/4 => r3c1,r3c2<>1, r1c6<>4, r3c1,r3c2<>6");
+        assert_eq!(als_xz(&sudoku, &mut None), Some(step_res));
+    }
+
+    #[test]
+    fn test_als_xy_wing_example1() {
+        let sudoku = Sudoku::from_pencilmarks(ALS_XY_WING_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::AlsXyWing {
+            excluded_candidates: vec![(Pos::new(9), Value::new(2))],
+            sets: [
+                vec![Pos::new(10), Pos::new(12), Pos::new(13), Pos::new(14), Pos::new(15)],
+                vec![Pos::new(45)],
+                vec![Pos::new(28), Pos::new(64), Pos::new(73)],
+            ],
+            restricted_commons: [Value::new(5), Value::new(8)],
+        };
+        assert_eq!(step_res.to_string(), "ALS-XY-Wing: A=r2c2,r2c4,r2c5,r2c6,r2c7 B=r6c1 C=r4c2,r8c2,r9c2 X=6 Y=9 => r2c1<>3");
+        let mut sets = None;
+        assert_eq!(als_xz(&sudoku, &mut sets), None);
+        assert!(sets.is_some());
+        assert_eq!(als_xy_wing(&sudoku, &mut sets), Some(step_res));
+    }
+
+    #[test]
+    fn test_death_blossom_example1() {
+        let sudoku = Sudoku::from_pencilmarks(DEATH_BLOSSOM_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::DeathBlossom {
+            excluded_candidates: vec![(Pos::new(47), Value::new(6))],
+            stem: Pos::new(18),
+            petals: vec![
+                (Value::new(2), vec![Pos::new(2), Pos::new(65)]),
+                (Value::new(3), vec![Pos::new(36)]),
+                (Value::new(7), vec![Pos::new(45), Pos::new(46), Pos::new(48), Pos::new(51)]),
+            ],
+        };
+        assert_eq!(step_res.to_string(), "Death Blossom: r3c1 (3) r1c3,r8c3 (4) r5c1 (8) r6c1,r6c2,r6c4,r6c7 => r6c3<>7");
+        assert_eq!(death_blossom(&sudoku, &mut None), Some(step_res));
+    }
+}
@@ -0,0 +1,629 @@
+use super::{
+    als::{almost_locked_sets, common_neighbors, AlmostLockedSet},
+    coloring::conjugate_pairs,
+    locked_candidate::intersections,
+    wings::bivalue_cells,
+    StrategyResult,
+};
+use crate::{
+    solver::{House, PosBitSet},
+    Pos, Sudoku, Value,
+};
+use std::collections::VecDeque;
+
+/// How two candidates in a chain are linked
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LinkType {
+    /// At least one of the candidates is true
+    Strong,
+    /// At most one of the candidates is true
+    Weak,
+    /// A strong link between two values of an almost locked set: if the first value isn't in the
+    /// set, the rest of the values are locked into it, including the second
+    Als,
+}
+
+/// The shape of an alternating inference chain
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AicKind {
+    /// An open chain, where one of the ends must be true, so any candidate weakly linked to both
+    /// ends is excluded
+    Chain,
+    /// A chain starting and ending with strong links to the same candidate, which must be true
+    DiscontinuousLoop,
+    /// A closed loop, where every weak link is also strong, so candidates seeing both ends of any
+    /// weak link are excluded
+    ContinuousLoop,
+}
+
+pub(crate) type Chain = Vec<(Pos, Value, LinkType)>;
+/// A chain where each node is the value in any of the positions, so it can be a group of cells in
+/// a block and line intersection, or the cells of an almost locked set
+pub(crate) type GroupedChain = Vec<(Vec<Pos>, Value, LinkType)>;
+
+/// A chain as found in the link graph, before converting the nodes to positions
+type NodeChain = Vec<(PosBitSet, Value, LinkType)>;
+
+/// The largest almost locked sets to use as nodes
+const MAX_ALS_SIZE: usize = 4;
+
+#[inline]
+pub(crate) fn node_idx(pos: Pos, val: Value) -> usize {
+    pos.as_usize() * Value::N + val.as_usize()
+}
+
+/// Strong and weak links between nodes, where each node is a value in one of some positions. The
+/// first nodes are the single candidates, indexed by `node_idx`, followed by any groups.
+pub(crate) struct LinkGraph {
+    nodes: Vec<(PosBitSet, Value)>,
+    strong: Vec<Vec<(usize, LinkType)>>,
+    weak: Vec<Vec<usize>>,
+}
+
+impl LinkGraph {
+    fn new() -> Self {
+        Self {
+            nodes: (0 .. Pos::N * Value::N)
+                .map(|idx| (std::iter::once(Pos::new(idx / Value::N)).collect(), Value::new(idx % Value::N)))
+                .collect(),
+            strong: vec![Vec::new(); Pos::N * Value::N],
+            weak: vec![Vec::new(); Pos::N * Value::N],
+        }
+    }
+
+    /// The node for the value in any of the positions, adding it if needed
+    fn node(&mut self, positions: PosBitSet, val: Value) -> usize {
+        if positions.len() == 1 {
+            return node_idx(positions.iter().next().unwrap(), val);
+        }
+        if let Some(idx) = self.nodes[Pos::N * Value::N ..].iter().position(|&node| node == (positions, val)) {
+            return Pos::N * Value::N + idx;
+        }
+        self.nodes.push((positions, val));
+        self.strong.push(Vec::new());
+        self.weak.push(Vec::new());
+        self.nodes.len() - 1
+    }
+
+    fn add_link<T: Copy + PartialEq>(links: &mut [Vec<T>], idx1: usize, link1: T, idx2: usize, link2: T) {
+        if !links[idx1].contains(&link2) { links[idx1].push(link2) }
+        if !links[idx2].contains(&link1) { links[idx2].push(link1) }
+    }
+
+    /// Strong links between candidates which see each other can also be used as weak links
+    fn add_strong_link(&mut self, idx1: usize, idx2: usize, link_type: LinkType) {
+        Self::add_link(&mut self.strong, idx1, (idx1, link_type), idx2, (idx2, link_type));
+        if link_type == LinkType::Strong {
+            Self::add_link(&mut self.weak, idx1, idx1, idx2, idx2);
+        }
+    }
+
+    fn link_type(&self, idx1: usize, idx2: usize) -> LinkType {
+        self.strong[idx1].iter().find(|&&(idx, _)| idx == idx2).map_or(LinkType::Weak, |&(_, link_type)| link_type)
+    }
+
+    /// Strong links between the only two positions of the value in a house
+    fn add_conjugate_links(&mut self, sudoku: &Sudoku, val: Value) {
+        for (pos1, pos2) in conjugate_pairs(sudoku.get_candidates_by_value(val)) {
+            self.add_strong_link(node_idx(pos1, val), node_idx(pos2, val), LinkType::Strong);
+        }
+    }
+
+    /// Strong links between the two candidates of each of the cells
+    fn add_bivalue_links(&mut self, sudoku: &Sudoku, cells: PosBitSet) {
+        for pos in cells.iter() {
+            let mut vals = sudoku.get_candidates_by_pos(pos).iter();
+            let val1 = vals.next().unwrap();
+            let val2 = vals.next().unwrap();
+            self.add_strong_link(node_idx(pos, val1), node_idx(pos, val2), LinkType::Strong);
+        }
+    }
+
+    /// Strong links between groups of the value, where all of the value in a house is in two
+    /// block and line intersections
+    fn add_group_links(&mut self, sudoku: &Sudoku, val: Value) {
+        let groups: Vec<_> = intersections(sudoku.get_candidates_by_value(val)).collect();
+        for house in House::iter() {
+            // Blocks can be split into intersections with either rows or columns
+            for &rows in &[true, false] {
+                let parts: Vec<PosBitSet> = groups.iter()
+                    .filter(|&&(block, line, _)| match house.as_block() {
+                        Some(block2) => block == block2 && line.as_row().is_some() == rows,
+                        None => rows && House::from_line(line) == house,
+                    })
+                    .map(|&(_, _, positions)| positions)
+                    .collect();
+                if parts.len() != 2 || (parts[0].len() == 1 && parts[1].len() == 1) { continue }
+                let idx1 = self.node(parts[0], val);
+                let idx2 = self.node(parts[1], val);
+                self.add_strong_link(idx1, idx2, LinkType::Strong);
+            }
+        }
+    }
+
+    /// Links between every pair of values of each almost locked set
+    fn add_als_links(&mut self, sudoku: &Sudoku, sets: &[AlmostLockedSet]) {
+        for als in sets {
+            for val1 in als.values.iter() {
+                for val2 in als.values.iter().filter(|&val2| val2 > val1) {
+                    let idx1 = self.node(als.positions, val1);
+                    let idx2 = self.node(als.positions, val2);
+                    // Nodes are only true when the value is in one of the cells that still has it
+                    debug_assert!(!als.positions_of(sudoku, val1).is_empty());
+                    self.add_strong_link(idx1, idx2, LinkType::Als);
+                }
+            }
+        }
+    }
+
+    /// Weak links between every pair of nodes of the value where each candidate of one sees every
+    /// candidate of the other
+    fn add_weak_links(&mut self, sudoku: &Sudoku, val: Value) {
+        let candidates = sudoku.get_candidates_by_value(val);
+        let nodes: Vec<(usize, PosBitSet)> = self.nodes.iter().enumerate()
+            .filter(|&(_, &(_, val2))| val2 == val)
+            .map(|(idx, &(positions, _))| (idx, positions & candidates))
+            .filter(|(_, positions)| !positions.is_empty())
+            .collect();
+        for (i, &(idx1, positions1)) in nodes.iter().enumerate() {
+            let neighbors = common_neighbors(positions1);
+            for &(idx2, positions2) in &nodes[i + 1 ..] {
+                if positions2.difference(neighbors).is_empty() {
+                    Self::add_link(&mut self.weak, idx1, idx1, idx2, idx2);
+                }
+            }
+        }
+    }
+
+    /// Weak links between the candidates of each cell
+    fn add_cell_links(&mut self, sudoku: &Sudoku) {
+        for pos in Pos::iter() {
+            let vals = sudoku.get_candidates_by_pos(pos);
+            for val1 in vals.iter() {
+                for val2 in vals.iter().filter(|&val2| val2 > val1) {
+                    Self::add_link(&mut self.weak, node_idx(pos, val1), node_idx(pos, val1), node_idx(pos, val2), node_idx(pos, val2));
+                }
+            }
+        }
+    }
+}
+
+/// Whether no nodes overlap, except for runs of consecutive nodes in the same cells. The last node
+/// may also overlap the first run, as chains can loop back to where they started.
+fn is_simple(nodes: &[(PosBitSet, Value)]) -> bool {
+    let mut seen = PosBitSet::NONE;
+    let mut run = PosBitSet::NONE;
+    let mut first_run = None;
+    for (idx, &(positions, _)) in nodes.iter().enumerate() {
+        if idx > 0 && (positions & run).is_empty() {
+            first_run.get_or_insert(run);
+            seen |= run;
+            run = PosBitSet::NONE;
+        }
+        let overlapping = if idx == nodes.len() - 1 { seen.difference(first_run.unwrap_or(run)) } else { seen };
+        if !(positions & overlapping).is_empty() { return false }
+        run |= positions;
+    }
+    true
+}
+
+/// Finds the shortest chain starting at `start`, alternating strong and weak links and starting
+/// and ending with a strong link, for which `conclusions` finds something. Chains have between
+/// `min_nodes` and `max_nodes` nodes.
+fn shortest_chain<T, F>(graph: &LinkGraph, start: usize, min_nodes: usize, max_nodes: usize, conclusions: F)
+    -> Option<(NodeChain, T)>
+where
+    F: Fn(&[(PosBitSet, Value)]) -> Option<T>,
+{
+    // States are a node, and whether the next link must be strong (0) or weak (1)
+    const NO_PARENT: usize = usize::MAX;
+    let mut parents = vec![NO_PARENT; graph.nodes.len() * 2];
+    let mut depths = vec![0; graph.nodes.len() * 2];
+    let start_state = start * 2;
+    parents[start_state] = start_state;
+    depths[start_state] = 1;
+
+    let mut queue = VecDeque::new();
+    queue.push_back(start_state);
+    while let Some(state) = queue.pop_front() {
+        let next_weak = state % 2 == 1;
+        let depth = depths[state];
+
+        if next_weak && depth >= min_nodes {
+            let mut path = Vec::with_capacity(depth);
+            let mut cur = state;
+            loop {
+                path.push(cur / 2);
+                if parents[cur] == cur { break }
+                cur = parents[cur];
+            }
+            path.reverse();
+            let nodes: Vec<_> = path.iter().map(|&idx| graph.nodes[idx]).collect();
+            if is_simple(&nodes) {
+                if let Some(res) = conclusions(&nodes) {
+                    let chain = path.iter().enumerate()
+                        .map(|(i, &idx)| {
+                            let link_type = if i % 2 == 0 { graph.link_type(idx, path[i + 1]) } else { LinkType::Weak };
+                            (graph.nodes[idx].0, graph.nodes[idx].1, link_type)
+                        })
+                        .collect();
+                    return Some((chain, res));
+                }
+            }
+        }
+        if depth >= max_nodes { continue }
+
+        let next_nodes: Box<dyn Iterator<Item = usize>> = if next_weak {
+            Box::new(graph.weak[state / 2].iter().cloned())
+        } else {
+            Box::new(graph.strong[state / 2].iter().map(|&(idx, _)| idx))
+        };
+        for idx in next_nodes {
+            let state2 = idx * 2 + if next_weak { 0 } else { 1 };
+            if parents[state2] != NO_PARENT { continue }
+            parents[state2] = state;
+            depths[state2] = depth + 1;
+            queue.push_back(state2);
+        }
+    }
+    None
+}
+
+/// Finds the shortest chain from any of the starting nodes, preferring the first found
+fn shortest_chain_from<T, F>(graph: &LinkGraph, starts: impl Iterator<Item = usize>, min_nodes: usize,
+    max_nodes: usize, conclusions: F) -> Option<(NodeChain, T)>
+where
+    F: Fn(&[(PosBitSet, Value)]) -> Option<T>,
+{
+    let mut best: Option<(NodeChain, T)> = None;
+    for start in starts {
+        // Only look for chains shorter than the best so far
+        let max_nodes = match &best {
+            Some((chain, _)) => max_nodes.min(chain.len() - 1),
+            None => max_nodes,
+        };
+        if let Some(res) = shortest_chain(graph, start, min_nodes, max_nodes, &conclusions) {
+            best = Some(res);
+        }
+    }
+    best
+}
+
+/// Converts a chain of single candidates
+fn to_chain(chain: NodeChain) -> Chain {
+    chain.into_iter().map(|(positions, val, link_type)| (positions.iter().next().unwrap(), val, link_type)).collect()
+}
+
+fn non_empty(candidates: Vec<(Pos, Value)>) -> Option<Vec<(Pos, Value)>> {
+    if candidates.is_empty() { None } else { Some(candidates) }
+}
+
+/// The single position of a node
+#[inline]
+fn single_pos(positions: PosBitSet) -> Pos {
+    positions.iter().next().unwrap()
+}
+
+/// Single digit chains, where the ends of the chain can't both be false, so any candidate seeing
+/// both ends is excluded
+pub(crate) fn x_chain(sudoku: &Sudoku, max_nodes: usize) -> Option<StrategyResult> {
+    let mut best: Option<(NodeChain, Vec<(Pos, Value)>)> = None;
+    for val in Value::iter() {
+        let candidates = sudoku.get_candidates_by_value(val);
+        if candidates.is_empty() { continue }
+
+        let mut graph = LinkGraph::new();
+        graph.add_conjugate_links(sudoku, val);
+        graph.add_weak_links(sudoku, val);
+
+        let max_nodes = match &best {
+            Some((chain, _)) => max_nodes.min(chain.len() - 1),
+            None => max_nodes,
+        };
+        let starts = candidates.iter().map(|pos| node_idx(pos, val));
+        let res = shortest_chain_from(&graph, starts, 4, max_nodes, |nodes| {
+            let start = single_pos(nodes[0].0);
+            let end = single_pos(nodes[nodes.len() - 1].0);
+            if start == end { return None }
+            non_empty((start.neighbors_bitset() & end.neighbors_bitset() & candidates).iter().map(|pos| (pos, val)).collect())
+        });
+        if res.is_some() { best = res }
+    }
+
+    best.map(|(chain, excluded_candidates)| StrategyResult::XChain { excluded_candidates, chain: to_chain(chain) })
+}
+
+/// Chains of bivalue cells, starting and ending with the same value, so any candidate of that
+/// value seeing both ends is excluded
+pub(crate) fn xy_chain(sudoku: &Sudoku, max_nodes: usize) -> Option<StrategyResult> {
+    let cells = bivalue_cells(sudoku);
+    let mut graph = LinkGraph::new();
+    graph.add_bivalue_links(sudoku, cells);
+    for val in Value::iter() {
+        graph.add_weak_links(sudoku, val);
+    }
+
+    let starts = cells.iter().flat_map(|pos| sudoku.get_candidates_by_pos(pos).iter().map(move |val| node_idx(pos, val)));
+    let res = shortest_chain_from(&graph, starts, 6, max_nodes, |nodes| {
+        let (start, val) = (single_pos(nodes[0].0), nodes[0].1);
+        let (end, end_val) = (single_pos(nodes[nodes.len() - 1].0), nodes[nodes.len() - 1].1);
+        if val != end_val || start == end { return None }
+        non_empty((start.neighbors_bitset() & end.neighbors_bitset() & sudoku.get_candidates_by_value(val)).iter()
+            .map(|pos| (pos, val))
+            .collect())
+    });
+
+    res.map(|(chain, excluded_candidates)| StrategyResult::XyChain { excluded_candidates, chain: to_chain(chain) })
+}
+
+/// The positions of the value which can't be true at the same time as the node
+fn weakly_linked_positions(sudoku: &Sudoku, (positions, val): (PosBitSet, Value), val2: Value) -> PosBitSet {
+    let candidates = positions & sudoku.get_candidates_by_value(val);
+    if val == val2 {
+        common_neighbors(candidates) & sudoku.get_candidates_by_value(val)
+    } else if candidates.len() == 1 && sudoku.get_candidates_by_pos(single_pos(candidates)).contains(val2) {
+        candidates
+    } else {
+        PosBitSet::NONE
+    }
+}
+
+/// Whether two different nodes can't both be true
+fn is_weakly_linked(sudoku: &Sudoku, node1: (PosBitSet, Value), node2: (PosBitSet, Value)) -> bool {
+    let candidates2 = node2.0 & sudoku.get_candidates_by_value(node2.1);
+    !candidates2.is_empty() && candidates2.difference(weakly_linked_positions(sudoku, node1, node2.1)).is_empty()
+}
+
+/// The kind of chain, with its excluded and required candidates
+type AicConclusions = (AicKind, Vec<(Pos, Value)>, Vec<(Pos, Value)>);
+
+/// What can be concluded from a chain of nodes, or `None` if nothing
+fn aic_conclusions(sudoku: &Sudoku, nodes: &[(PosBitSet, Value)]) -> Option<AicConclusions> {
+    let start = nodes[0];
+    let end = nodes[nodes.len() - 1];
+    let mut excluded_candidates = Vec::new();
+    if start == end {
+        // The value must be in one of the start's cells
+        let (positions, val) = start;
+        let candidates = positions & sudoku.get_candidates_by_value(val);
+        if candidates.len() == 1 {
+            return Some((AicKind::DiscontinuousLoop, Vec::new(), vec![(single_pos(candidates), val)]));
+        }
+        let excluded = common_neighbors(candidates) & sudoku.get_candidates_by_value(val);
+        excluded_candidates.extend(excluded.iter().map(|pos| (pos, val)));
+        return non_empty(excluded_candidates).map(|excluded| (AicKind::DiscontinuousLoop, excluded, Vec::new()));
+    }
+
+    if is_weakly_linked(sudoku, end, start) {
+        // Every other link is weak, as is the link closing the loop
+        for idx in (1..nodes.len()).step_by(2) {
+            let (positions1, val1) = nodes[idx];
+            let (positions2, val2) = nodes[(idx + 1) % nodes.len()];
+            let candidates1 = positions1 & sudoku.get_candidates_by_value(val1);
+            let candidates2 = positions2 & sudoku.get_candidates_by_value(val2);
+            if val1 != val2 {
+                let pos = single_pos(candidates1);
+                let others = sudoku.get_candidates_by_pos(pos).iter().filter(|&val| val != val1 && val != val2);
+                excluded_candidates.extend(others.map(|val| (pos, val)));
+            } else {
+                let positions = common_neighbors(candidates1 | candidates2) & sudoku.get_candidates_by_value(val1);
+                excluded_candidates.extend(positions.iter().map(|pos| (pos, val1)));
+            }
+        }
+        excluded_candidates.sort_unstable();
+        excluded_candidates.dedup();
+        return non_empty(excluded_candidates).map(|excluded| (AicKind::ContinuousLoop, excluded, Vec::new()));
+    }
+
+    for val in Value::iter() {
+        let positions = weakly_linked_positions(sudoku, start, val) & weakly_linked_positions(sudoku, end, val);
+        excluded_candidates.extend(positions.iter().map(|pos| (pos, val)));
+    }
+    non_empty(excluded_candidates).map(|excluded| (AicKind::Chain, excluded, Vec::new()))
+}
+
+/// Alternating inference chains of any candidates, linked strongly by conjugate pairs and bivalue
+/// cells, and weakly by candidates which see each other. With `grouped`, nodes can also be groups
+/// of candidates in a block and line intersection, and with `als`, the cells of almost locked sets.
+pub(crate) fn aic(sudoku: &Sudoku, max_nodes: usize, grouped: bool, als: bool) -> Option<StrategyResult> {
+    let mut graph = LinkGraph::new();
+    graph.add_bivalue_links(sudoku, bivalue_cells(sudoku));
+    for val in Value::iter() {
+        graph.add_conjugate_links(sudoku, val);
+        if grouped {
+            graph.add_group_links(sudoku, val);
+        }
+    }
+    if als {
+        // Bivalue cells are already linked
+        let sets: Vec<_> = almost_locked_sets(sudoku, MAX_ALS_SIZE).into_iter().filter(|als| als.positions.len() > 1).collect();
+        graph.add_als_links(sudoku, &sets);
+    }
+    for val in Value::iter() {
+        graph.add_weak_links(sudoku, val);
+    }
+    graph.add_cell_links(sudoku);
+
+    // Only start with the chains which are just single candidates, then try the rest
+    let single_starts = Pos::iter().flat_map(|pos| sudoku.get_candidates_by_pos(pos).iter().map(move |val| node_idx(pos, val)));
+    let starts = single_starts.chain(Pos::N * Value::N .. graph.nodes.len());
+    let res = shortest_chain_from(&graph, starts, 4, max_nodes, |nodes| aic_conclusions(sudoku, nodes));
+
+    res.map(|(chain, (kind, excluded_candidates, required_candidates))| StrategyResult::Aic {
+        excluded_candidates,
+        required_candidates,
+        chain: chain.into_iter().map(|(positions, val, link_type)| (positions.iter().collect(), val, link_type)).collect(),
+        kind,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::tests::check_example;
+
+    #[test]
+    fn test_x_chain_example1() {
+        let line = "7..5.12.9.9.7.61..1..293....31469.7...7.28.......576...248753.1.1..3..5..7..1...8";
+        let step_res = StrategyResult::XChain {
+            excluded_candidates: vec![(Pos::new(19), Value::new(5))],
+            chain: vec![
+                (Pos::new(26), Value::new(5), LinkType::Strong), (Pos::new(71), Value::new(5), LinkType::Weak),
+                (Pos::new(61), Value::new(5), LinkType::Strong), (Pos::new(54), Value::new(5), LinkType::Weak),
+                (Pos::new(36), Value::new(5), LinkType::Strong), (Pos::new(37), Value::new(5), LinkType::Weak),
+            ],
+        };
+        check_example(|sudoku| x_chain(sudoku, usize::MAX), line, Some(step_res.clone()));
+        check_example(|sudoku| x_chain(sudoku, 6), line, Some(step_res));
+        check_example(|sudoku| x_chain(sudoku, 4), line, None);
+    }
+
+    #[test]
+    fn test_xy_chain_example1() {
+        let line = "624..1.5.3596481271782.5...2.......17.1.8.....4.1.2....128..76.463927.1...7.16.3.";
+        let step_res = StrategyResult::XyChain {
+            excluded_candidates: vec![(Pos::new(41), Value::new(2))],
+            chain: vec![
+                (Pos::new(37), Value::new(2), LinkType::Strong), (Pos::new(37), Value::new(8), LinkType::Weak),
+                (Pos::new(43), Value::new(8), LinkType::Strong), (Pos::new(43), Value::new(3), LinkType::Weak),
+                (Pos::new(25), Value::new(3), LinkType::Strong), (Pos::new(25), Value::new(8), LinkType::Weak),
+                (Pos::new(22), Value::new(8), LinkType::Strong), (Pos::new(22), Value::new(2), LinkType::Weak),
+                (Pos::new(58), Value::new(2), LinkType::Strong), (Pos::new(58), Value::new(4), LinkType::Weak),
+                (Pos::new(75), Value::new(4), LinkType::Strong), (Pos::new(75), Value::new(3), LinkType::Weak),
+                (Pos::new(59), Value::new(3), LinkType::Strong), (Pos::new(59), Value::new(2), LinkType::Weak),
+            ],
+        };
+        check_example(|sudoku| xy_chain(sudoku, usize::MAX), line, Some(step_res.clone()));
+        check_example(|sudoku| xy_chain(sudoku, 14), line, Some(step_res));
+        check_example(|sudoku| xy_chain(sudoku, 12), line, None);
+    }
+
+    #[test]
+    fn test_aic_example1() {
+        let line = "6.9512......349.5...3786.299..865.....549..8...412.9.579.63154...6.5...7..1.7...3";
+        let step_res = StrategyResult::Aic {
+            excluded_candidates: vec![(Pos::new(17), Value::new(7))],
+            required_candidates: Vec::new(),
+            chain: vec![
+                (vec![Pos::new(8)], Value::new(7), LinkType::Strong),
+                (vec![Pos::new(8)], Value::new(3), LinkType::Weak),
+                (vec![Pos::new(24)], Value::new(3), LinkType::Strong),
+                (vec![Pos::new(24)], Value::new(0), LinkType::Weak),
+                (vec![Pos::new(69)], Value::new(0), LinkType::Strong),
+                (vec![Pos::new(70)], Value::new(0), LinkType::Weak),
+                (vec![Pos::new(70)], Value::new(8), LinkType::Strong),
+                (vec![Pos::new(79)], Value::new(8), LinkType::Weak),
+                (vec![Pos::new(79)], Value::new(5), LinkType::Strong),
+                (vec![Pos::new(78)], Value::new(5), LinkType::Weak),
+                (vec![Pos::new(15)], Value::new(5), LinkType::Strong),
+                (vec![Pos::new(17)], Value::new(5), LinkType::Weak),
+            ],
+            kind: AicKind::Chain,
+        };
+        check_example(|sudoku| aic(sudoku, usize::MAX, false, false), line, Some(step_res.clone()));
+        check_example(|sudoku| aic(sudoku, 12, false, false), line, Some(step_res));
+        check_example(|sudoku| aic(sudoku, 10, false, false), line, None);
+    }
+
+    #[test]
+    fn test_continuous_loop_example1() {
+        let line = "..193....274581963.3.....8..9.7..3.4..5..36.73.7.2.5.8.1324.87.7................6";
+        let step_res = StrategyResult::Aic {
+            excluded_candidates: vec![(Pos::new(31), Value::new(0)), (Pos::new(39), Value::new(0))],
+            required_candidates: Vec::new(),
+            chain: vec![
+                (vec![Pos::new(40)], Value::new(0), LinkType::Strong),
+                (vec![Pos::new(40)], Value::new(8), LinkType::Weak),
+                (vec![Pos::new(43)], Value::new(8), LinkType::Strong),
+                (vec![Pos::new(52)], Value::new(8), LinkType::Weak),
+                (vec![Pos::new(52)], Value::new(0), LinkType::Strong),
+                (vec![Pos::new(48)], Value::new(0), LinkType::Weak),
+            ],
+            kind: AicKind::ContinuousLoop,
+        };
+        check_example(|sudoku| aic(sudoku, usize::MAX, false, false), line, Some(step_res));
+    }
+
+    #[test]
+    fn test_discontinuous_loop_example1() {
+        let line = "....28.......43526..4..1.38.1..698.4...3.42..4...156.3..81.....5...9...297.......";
+        let step_res = StrategyResult::Aic {
+            excluded_candidates: Vec::new(),
+            required_candidates: vec![(Pos::new(9), Value::new(0))],
+            chain: vec![
+                (vec![Pos::new(9)], Value::new(0), LinkType::Strong),
+                (vec![Pos::new(11)], Value::new(0), LinkType::Weak),
+                (vec![Pos::new(0)], Value::new(0), LinkType::Strong),
+                (vec![Pos::new(9)], Value::new(0), LinkType::Weak),
+            ],
+            kind: AicKind::DiscontinuousLoop,
+        };
+        check_example(|sudoku| aic(sudoku, usize::MAX, false, false), line, Some(step_res));
+    }
+
+    const GROUPED_AIC_EXAMPLE1: &str = "\
+3       126    5    | 9      8   16   | 26   7     4
+4       69     8    | 3      2   7    | 569  59    1
+1269    7      1269 | 16     5   4    | 269  8     3
+--------------------+-----------------+--------------
+129     4      129  | 7      6   8    | 19   3     5
+7       8      19   | 12     3   5    | 4    6     29
+56      56     3    | 4      19  129  | 7    12    8
+--------------------+-----------------+--------------
+2568    12569  4    | 12568  19  1269 | 3    1259  7
+25      3      7    | 125    4   129  | 8    1259  6
+125689  1269   69   | 12568  7   3    | 159  4     29";
+
+    const ALS_AIC_EXAMPLE1: &str = "\
+359   239    234  | 7     358   3489 | 6     1     459
+3579  367    3467 | 3459  35    1    | 8     479   2
+1579  19     8    | 2     6     49   | 57    4579  3
+------------------+------------------+-----------------
+379   2379   1237 | 8     4     237  | 15    3569  1569
+6     4      13   | 13    9     5    | 2     8     7
+3789  23789  5    | 6     137   237  | 14    349   149
+------------------+------------------+-----------------
+1378  13678  367  | 1459  1357  3479 | 1457  2     1456
+2     5      367  | 134   1378  3478 | 9     467   146
+4     17     9    | 15    2     6    | 3     57    8";
+
+    #[test]
+    fn test_grouped_aic_example1() {
+        let sudoku = Sudoku::from_pencilmarks(GROUPED_AIC_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::Aic {
+            excluded_candidates: vec![(Pos::new(52), Value::new(0))],
+            required_candidates: Vec::new(),
+            chain: vec![
+                (vec![Pos::new(49)], Value::new(0), LinkType::Strong),
+                (vec![Pos::new(58)], Value::new(0), LinkType::Weak),
+                (vec![Pos::new(66), Pos::new(68)], Value::new(0), LinkType::Strong),
+                (vec![Pos::new(70)], Value::new(0), LinkType::Weak),
+            ],
+            kind: AicKind::Chain,
+        };
+        assert_eq!(step_res.to_string(), "AIC: (1)r6c5=r7c5-r8c46=r8c8 => r6c8<>1");
+        assert_ne!(aic(&sudoku, usize::MAX, false, false), Some(step_res.clone()));
+        assert_eq!(aic(&sudoku, 4, true, false), Some(step_res));
+    }
+
+    #[test]
+    fn test_als_aic_example1() {
+        let sudoku = Sudoku::from_pencilmarks(ALS_AIC_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::Aic {
+            excluded_candidates: vec![(Pos::new(45), Value::new(2)), (Pos::new(46), Value::new(2))],
+            required_candidates: Vec::new(),
+            chain: vec![
+                (vec![Pos::new(38)], Value::new(2), LinkType::Strong),
+                (vec![Pos::new(38)], Value::new(0), LinkType::Weak),
+                (vec![Pos::new(39)], Value::new(0), LinkType::Strong),
+                (vec![Pos::new(49)], Value::new(0), LinkType::Weak),
+                (vec![Pos::new(51), Pos::new(52), Pos::new(53)], Value::new(0), LinkType::Als),
+                (vec![Pos::new(51), Pos::new(52), Pos::new(53)], Value::new(2), LinkType::Weak),
+            ],
+            kind: AicKind::Chain,
+        };
+        assert_eq!(step_res.to_string(), "AIC: (3=1)r5c3-r5c4=r6c5-(1=3)r6c789 => r6c1,r6c2<>3");
+        assert_ne!(aic(&sudoku, usize::MAX, true, false), Some(step_res.clone()));
+        assert_eq!(aic(&sudoku, 6, true, true), Some(step_res));
+    }
+}
@@ -111,15 +111,21 @@ impl Coloring {
     }
 }
 
-fn build_coloring(positions: PosBitSet) -> Coloring {
-    let mut ret = Coloring::new();
-    for house in House::iter() {
+/// Pairs of positions which are the only two in some house, out of the positions of a value
+pub(crate) fn conjugate_pairs(positions: PosBitSet) -> impl Iterator<Item = (Pos, Pos)> {
+    House::iter().filter_map(move |house| {
         let candidates = positions & house.members_bitset();
-        if candidates.len() != 2 { continue }
+        if candidates.len() != 2 { return None }
         let mut candidates_iter = candidates.iter();
         let pos1 = candidates_iter.next().unwrap();
         let pos2 = candidates_iter.next().unwrap();
+        Some((pos1, pos2))
+    })
+}
 
+fn build_coloring(positions: PosBitSet) -> Coloring {
+    let mut ret = Coloring::new();
+    for (pos1, pos2) in conjugate_pairs(positions) {
         ret.add_conjugate_pair(pos1, pos2);
     }
     ret
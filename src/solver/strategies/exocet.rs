@@ -0,0 +1,179 @@
+use super::StrategyResult;
+use crate::{
+    solver::{Block, Col, Line, PosBitSet, Row, ValueBitSet},
+    Pos, Sudoku, Value,
+};
+
+/// The row and column of the position
+fn lines_containing(pos: Pos) -> [Line; 2] {
+    [Line::from_row(Row::new(pos.row() as usize)), Line::from_col(Col::new(pos.col() as usize))]
+}
+
+/// The line through the position which crosses `line`
+fn cross_line(line: Line, pos: Pos) -> Line {
+    let [row, col] = lines_containing(pos);
+    if line.as_row().is_some() { col } else { row }
+}
+
+/// Whether the positions can all be covered by two rows or columns
+fn covered_by_two_lines(positions: PosBitSet) -> bool {
+    let first = match positions.iter().next() {
+        Some(pos) => pos,
+        None => return true,
+    };
+    lines_containing(first).iter().any(|line| {
+        let rest = positions.difference(line.members_bitset());
+        match rest.iter().next() {
+            Some(pos) => lines_containing(pos).iter().any(|line2| rest.difference(line2.members_bitset()).is_empty()),
+            None => true,
+        }
+    })
+}
+
+/// The positions where the value is placed or is a candidate
+fn value_positions(sudoku: &Sudoku, val: Value) -> PosBitSet {
+    let mut positions = sudoku.get_candidates_by_value(val);
+    for pos in Pos::iter().filter(|&pos| sudoku.get_value(pos) == Some(val)) {
+        positions.insert(pos);
+    }
+    positions
+}
+
+/// Two base cells in a block and line intersection, with three or four candidates between them,
+/// and two target cells in the other blocks of the band, off the base line. The lines crossing the
+/// band through the targets and through the third cell of the intersection are the cross-lines.
+/// If each base value can be covered by two lines in the cross-lines outside the band, and it
+/// isn't in the targets' other cells off the base line in the band, then it can't fill all three
+/// cross-lines outside the band, so must be in one of the targets. The targets hold the base
+/// values, so any other candidates are excluded from the targets, and base candidates which
+/// aren't in either target are excluded from the base cells.
+pub(crate) fn junior_exocet(sudoku: &Sudoku) -> Option<StrategyResult> {
+    for block in Block::iter() {
+        for line in block.intersecting_lines_iter() {
+            let intersection: Vec<Pos> = (block.members_bitset() & line.members_bitset()).iter().collect();
+            let band: Vec<Block> = Block::iter()
+                .filter(|&block2| block2 != block && !(block2.members_bitset() & line.members_bitset()).is_empty())
+                .collect();
+            let band_positions = band.iter().fold(block.members_bitset(), |positions, block2| positions | block2.members_bitset());
+
+            for idx in 0 .. intersection.len() {
+                let escape_pos = intersection[idx];
+                let mut base = [intersection[(idx + 1) % 3], intersection[(idx + 2) % 3]];
+                base.sort_unstable();
+                let values = sudoku.get_candidates_by_pos(base[0]) | sudoku.get_candidates_by_pos(base[1]);
+                if sudoku.get_candidates_by_pos(base[0]).is_empty() || sudoku.get_candidates_by_pos(base[1]).is_empty()
+                    || !(3 ..= 4).contains(&values.len())
+                {
+                    continue
+                }
+
+                // Targets can't contain a base value in their cross-line's other cell off the base line
+                let possible_targets = |target_block: Block| -> Vec<Pos> {
+                    target_block.members_iter()
+                        .filter(|&pos| !line.members_bitset().contains(pos))
+                        .filter(|&pos| !(sudoku.get_candidates_by_pos(pos) & values).is_empty())
+                        .filter(|&pos| {
+                            let companions = (cross_line(line, pos).members_bitset() & target_block.members_bitset())
+                                .difference(line.members_bitset());
+                            companions.iter().all(|pos2| pos2 == pos || companion_excludes(sudoku, pos2, values))
+                        })
+                        .collect()
+                };
+
+                for &target1 in &possible_targets(band[0]) {
+                    for &target2 in &possible_targets(band[1]) {
+                        let mut cross_lines = [cross_line(line, escape_pos), cross_line(line, target1), cross_line(line, target2)];
+                        let cross_positions = cross_lines.iter()
+                            .fold(PosBitSet::NONE, |positions, cross_line| positions | cross_line.members_bitset())
+                            .difference(band_positions);
+                        if !values.iter().all(|val| covered_by_two_lines(value_positions(sudoku, val) & cross_positions)) {
+                            continue
+                        }
+
+                        let targets = [target1, target2];
+                        let target_values = sudoku.get_candidates_by_pos(target1) | sudoku.get_candidates_by_pos(target2);
+                        let mut excluded_candidates = Vec::new();
+                        for &pos in &targets {
+                            for val in sudoku.get_candidates_by_pos(pos).difference(values).iter() {
+                                excluded_candidates.push((pos, val));
+                            }
+                        }
+                        for &pos in &base {
+                            for val in sudoku.get_candidates_by_pos(pos).difference(target_values).iter() {
+                                excluded_candidates.push((pos, val));
+                            }
+                        }
+                        excluded_candidates.sort_unstable();
+
+                        if !excluded_candidates.is_empty() {
+                            cross_lines.sort_unstable();
+                            return Some(StrategyResult::JuniorExocet {
+                                excluded_candidates,
+                                base,
+                                targets,
+                                values: values.iter().collect(),
+                                cross_lines,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Whether the cell can't hold any of the values
+fn companion_excludes(sudoku: &Sudoku, pos: Pos, values: ValueBitSet) -> bool {
+    match sudoku.get_value(pos) {
+        Some(val) => !values.contains(val),
+        None => (sudoku.get_candidates_by_pos(pos) & values).is_empty(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JUNIOR_EXOCET_EXAMPLE1: &str = "\
+9      5       6      | 7      14       148    | 2       34      134
+13478  3478    147    | 9      1245     6      | 1358    345     1345
+2      348     14     | 13458  145      148    | 135689  34569   7
+----------------------+------------------------+---------------------
+378    23678   27     | 126    1267     5      | 4       236     9
+4      1       249    | 246    3        249    | 7       8       256
+5      234679  2479   | 1246   8        12479  | 136     236     1236
+----------------------+------------------------+---------------------
+47     2479    3      | 2456   245679   2479   | 569     1       8
+147    2479    8      | 12456  1245679  3      | 569     245679  2456
+6      2479    124579 | 12458  124579   124789 | 359     234579  2345";
+
+    #[test]
+    fn test_junior_exocet_example1() {
+        let sudoku = Sudoku::from_pencilmarks(JUNIOR_EXOCET_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::JuniorExocet {
+            excluded_candidates: vec![(Pos::new(25), Value::new(2)), (Pos::new(25), Value::new(3)), (Pos::new(44), Value::new(1))],
+            base: [Pos::new(60), Pos::new(69)],
+            targets: [Pos::new(25), Pos::new(44)],
+            values: vec![Value::new(4), Value::new(5), Value::new(8)],
+            cross_lines: [Line::from_row(Row::new(2)), Line::from_row(Row::new(4)), Line::from_row(Row::new(8))],
+        };
+        assert_eq!(
+            step_res.to_string(),
+            "Junior Exocet: 5/6/9 at r7c7,r8c7 with targets r3c8,r5c9 and cross-lines r359 => r5c9<>2, r3c8<>3, r3c8<>4",
+        );
+        assert_eq!(junior_exocet(&sudoku), Some(step_res));
+    }
+
+    #[test]
+    fn test_covered_by_two_lines() {
+        let positions = |idxs: &[usize]| -> PosBitSet { idxs.iter().map(|&idx| Pos::new(idx)).collect() };
+        assert!(covered_by_two_lines(positions(&[])));
+        // Two rows
+        assert!(covered_by_two_lines(positions(&[0, 4, 8, 28, 35])));
+        // A row and a column
+        assert!(covered_by_two_lines(positions(&[0, 4, 8, 13, 40, 76])));
+        // Three cells in different rows and columns
+        assert!(!covered_by_two_lines(positions(&[0, 10, 20])));
+    }
+}
@@ -0,0 +1,499 @@
+use super::StrategyResult;
+use crate::{
+    solver::{Block, House, Line, PosBitSet},
+    Sudoku, Value,
+};
+
+pub(crate) fn x_wing(sudoku: &Sudoku) -> Option<StrategyResult> {
+    basic_fish(sudoku, 2)
+}
+
+pub(crate) fn swordfish(sudoku: &Sudoku) -> Option<StrategyResult> {
+    basic_fish(sudoku, 3)
+}
+
+pub(crate) fn jellyfish(sudoku: &Sudoku) -> Option<StrategyResult> {
+    basic_fish(sudoku, 4)
+}
+
+pub(crate) fn finned_x_wing(sudoku: &Sudoku) -> Option<StrategyResult> {
+    finned_fish(sudoku, 2)
+}
+
+pub(crate) fn finned_swordfish(sudoku: &Sudoku) -> Option<StrategyResult> {
+    finned_fish(sudoku, 3)
+}
+
+pub(crate) fn finned_jellyfish(sudoku: &Sudoku) -> Option<StrategyResult> {
+    finned_fish(sudoku, 4)
+}
+
+pub(crate) fn franken_fish(sudoku: &Sudoku, max_size: usize) -> Option<StrategyResult> {
+    complex_fish(sudoku, max_size, false)
+}
+
+pub(crate) fn mutant_fish(sudoku: &Sudoku, max_size: usize) -> Option<StrategyResult> {
+    complex_fish(sudoku, max_size, true)
+}
+
+/// All rows, or all columns
+fn lines_iter(rows: bool) -> impl Iterator<Item = Line> {
+    Line::iter().filter(move |line| line.as_row().is_some() == rows)
+}
+
+/// The candidates in the union of the lines
+fn candidates_in(candidates: PosBitSet, lines: &[Line]) -> PosBitSet {
+    lines.iter().fold(PosBitSet::NONE, |acc, line| acc | (candidates & line.members_bitset()))
+}
+
+/// A fish of size n is n base lines (all rows or all columns) where all candidates of a value lie
+/// in n cover lines crossing them. The value must be placed once in each base line, which uses up
+/// every cover line, so it can be removed from the rest of the cover lines.
+fn basic_fish(sudoku: &Sudoku, size: usize) -> Option<StrategyResult> {
+    for val in Value::iter() {
+        let candidates = sudoku.get_candidates_by_value(val);
+        if candidates.is_empty() { continue }
+
+        for &rows in &[true, false] {
+            // Lines with more candidates than the size can never be part of the fish. Lines with a
+            // single candidate are hidden singles, which we leave to the simpler strategy.
+            let possible_base_lines: Vec<Line> = lines_iter(rows)
+                .filter(|line| {
+                    let cnt = (candidates & line.members_bitset()).len();
+                    cnt >= 2 && cnt <= size
+                })
+                .collect();
+            let mut base_lines = Vec::with_capacity(size);
+            if let res@Some(_) = find_fish(val, candidates, &possible_base_lines, size, &mut base_lines) {
+                return res;
+            }
+        }
+    }
+    None
+}
+
+fn find_fish(val: Value, candidates: PosBitSet, possible_base_lines: &[Line], size: usize, base_lines: &mut Vec<Line>)
+    -> Option<StrategyResult>
+{
+    if base_lines.len() == size {
+        return handle_fish(val, candidates, base_lines);
+    }
+    let remaining = size - base_lines.len();
+    for (idx, &line) in possible_base_lines.iter().enumerate() {
+        if possible_base_lines.len() - idx < remaining { break }
+        base_lines.push(line);
+        // Prune as soon as the candidates span too many cover lines
+        if cover_lines(candidates, base_lines).len() <= size {
+            if let res@Some(_) = find_fish(val, candidates, &possible_base_lines[idx + 1 ..], size, base_lines) {
+                return res;
+            }
+        }
+        base_lines.pop();
+    }
+    None
+}
+
+/// The lines crossing the base lines which contain any of their candidates
+fn cover_lines(candidates: PosBitSet, base_lines: &[Line]) -> Vec<Line> {
+    let fish = candidates_in(candidates, base_lines);
+    lines_iter(base_lines[0].as_row().is_none())
+        .filter(|cross| !(fish & cross.members_bitset()).is_empty())
+        .collect()
+}
+
+fn handle_fish(val: Value, candidates: PosBitSet, base_lines: &[Line]) -> Option<StrategyResult> {
+    let cover_lines = cover_lines(candidates, base_lines);
+    // Fewer cover lines than base lines would mean the sudoku is unsolvable, which we leave to
+    // other strategies to find
+    if cover_lines.len() != base_lines.len() { return None }
+
+    let excluded = candidates_in(candidates, &cover_lines).difference(candidates_in(candidates, base_lines));
+    if excluded.is_empty() { return None }
+
+    Some(StrategyResult::Fish {
+        excluded_candidates: excluded.iter().map(|pos| (pos, val)).collect(),
+        value: val,
+        base_lines: base_lines.to_vec(),
+        cover_lines,
+    })
+}
+
+/// Calls `f` with each combination of `size` items, in order, until it returns `Some`
+fn find_combination<T: Copy, R>(items: &[T], size: usize, combination: &mut Vec<T>, f: &mut impl FnMut(&[T]) -> Option<R>)
+    -> Option<R>
+{
+    if combination.len() == size {
+        return f(combination);
+    }
+    let remaining = size - combination.len();
+    for (idx, &item) in items.iter().enumerate() {
+        if items.len() - idx < remaining { break }
+        combination.push(item);
+        if let res@Some(_) = find_combination(&items[idx + 1 ..], size, combination, f) {
+            return res;
+        }
+        combination.pop();
+    }
+    None
+}
+
+/// A finned fish is a fish where some candidates of the base lines (the fins) are outside the
+/// cover lines, but all in one block. Either one of the fins is the value, or the fish is, so any
+/// candidate in the cover lines which is in the fins' block can be removed. When some base line
+/// has only one candidate left without the fins, it's called a sashimi fish.
+fn finned_fish(sudoku: &Sudoku, size: usize) -> Option<StrategyResult> {
+    for val in Value::iter() {
+        let candidates = sudoku.get_candidates_by_value(val);
+        if candidates.is_empty() { continue }
+
+        for &rows in &[true, false] {
+            let possible_base_lines: Vec<Line> = lines_iter(rows)
+                .filter(|line| (candidates & line.members_bitset()).len() >= 2)
+                .collect();
+            let res = find_combination(&possible_base_lines, size, &mut Vec::with_capacity(size), &mut |base_lines| {
+                let fish = candidates_in(candidates, base_lines);
+                let possible_cover_lines = cover_lines(candidates, base_lines);
+                // With this few cover lines it's a basic fish (or the sudoku is unsolvable)
+                if possible_cover_lines.len() <= size { return None }
+                find_combination(&possible_cover_lines, size, &mut Vec::with_capacity(size), &mut |cover_lines| {
+                    handle_finned_fish(val, candidates, fish, base_lines, cover_lines)
+                })
+            });
+            if res.is_some() {
+                return res;
+            }
+        }
+    }
+    None
+}
+
+fn handle_finned_fish(val: Value, candidates: PosBitSet, fish: PosBitSet, base_lines: &[Line], cover_lines: &[Line])
+    -> Option<StrategyResult>
+{
+    let covered = candidates_in(fish, cover_lines);
+    let fins = fish.difference(covered);
+    let block = Block::from_pos(fins.iter().next()?);
+    if !fins.difference(block.members_bitset()).is_empty() { return None }
+
+    let excluded = (candidates_in(candidates, cover_lines) & block.members_bitset()).difference(fish);
+    if excluded.is_empty() { return None }
+
+    // Every base line needs a candidate in the cover lines, or the fins alone would be the base
+    let mut sashimi = false;
+    for line in base_lines {
+        match (covered & line.members_bitset()).len() {
+            0 => return None,
+            1 => sashimi = true,
+            _ => ()
+        }
+    }
+
+    Some(StrategyResult::FinnedFish {
+        excluded_candidates: excluded.iter().map(|pos| (pos, val)).collect(),
+        value: val,
+        base_lines: base_lines.to_vec(),
+        cover_lines: cover_lines.to_vec(),
+        fins: fins.iter().collect(),
+        sashimi,
+    })
+}
+
+/// Whether the base and cover houses make a franken fish, i.e. rows and blocks covered by columns
+/// and blocks (or the other way around). Anything else is a mutant fish.
+fn is_franken(base_houses: &[House], cover_houses: &[House]) -> bool {
+    let only = |houses: &[House], rows: bool| houses.iter().all(|house| {
+        house.as_block().is_some() || house.as_row().is_some() == rows
+    });
+    (only(base_houses, true) && only(cover_houses, false)) || (only(base_houses, false) && only(cover_houses, true))
+}
+
+/// Franken and mutant fish generalise fish to any houses: if the base houses don't share any
+/// candidates, each of them needs its own instance of the value, and when all of their candidates
+/// are in as many cover houses, each cover house gets exactly one of those. So any other candidate
+/// in the cover houses can be removed, as well as base candidates in more than one cover house.
+/// Only fish which include a block are franken fish, the rest are found by [`basic_fish`].
+fn complex_fish(sudoku: &Sudoku, max_size: usize, mutant: bool) -> Option<StrategyResult> {
+    for size in 2 ..= max_size {
+        for val in Value::iter() {
+            let candidates = sudoku.get_candidates_by_value(val);
+            if candidates.is_empty() { continue }
+
+            let possible_base_houses: Vec<House> = House::iter()
+                .filter(|house| (candidates & house.members_bitset()).len() >= 2)
+                .collect();
+            let res = find_combination(&possible_base_houses, size, &mut Vec::with_capacity(size), &mut |base_houses| {
+                let base_candidates: Vec<PosBitSet> = base_houses.iter()
+                    .map(|house| candidates & house.members_bitset())
+                    .collect();
+                let fish = base_candidates.iter().fold(PosBitSet::NONE, |acc, &cands| acc | cands);
+                if base_candidates.iter().map(|cands| cands.len()).sum::<usize>() != fish.len() { return None }
+
+                let mut cover_houses = Vec::with_capacity(size);
+                find_cover_houses(candidates, fish, base_houses, size, &mut cover_houses, &mut |cover_houses| {
+                    let is_kind = if mutant {
+                        !is_franken(base_houses, cover_houses)
+                    } else {
+                        is_franken(base_houses, cover_houses) &&
+                            base_houses.iter().chain(cover_houses).any(|house| house.as_block().is_some())
+                    };
+                    if !is_kind { return None }
+                    handle_complex_fish(val, candidates, fish, base_houses, cover_houses, mutant)
+                })
+            });
+            if res.is_some() {
+                return res;
+            }
+        }
+    }
+    None
+}
+
+/// Covers the candidates of the fish one at a time, trying each house containing the first
+/// uncovered candidate
+fn find_cover_houses(candidates: PosBitSet, uncovered: PosBitSet, base_houses: &[House], size: usize,
+    cover_houses: &mut Vec<House>, f: &mut impl FnMut(&[House]) -> Option<StrategyResult>) -> Option<StrategyResult>
+{
+    let pos = match uncovered.iter().next() {
+        Some(pos) => pos,
+        None => return f(cover_houses),
+    };
+    if cover_houses.len() == size { return None }
+
+    for &house in &House::containing(pos) {
+        if base_houses.contains(&house) || cover_houses.contains(&house) { continue }
+        cover_houses.push(house);
+        let uncovered = uncovered.difference(candidates & house.members_bitset());
+        if let res@Some(_) = find_cover_houses(candidates, uncovered, base_houses, size, cover_houses, f) {
+            return res;
+        }
+        cover_houses.pop();
+    }
+    None
+}
+
+fn handle_complex_fish(val: Value, candidates: PosBitSet, fish: PosBitSet, base_houses: &[House], cover_houses: &[House],
+    mutant: bool) -> Option<StrategyResult>
+{
+    let mut covered = PosBitSet::NONE;
+    let mut covered_twice = PosBitSet::NONE;
+    for house in cover_houses {
+        let cands = candidates & house.members_bitset();
+        covered_twice |= covered & cands;
+        covered |= cands;
+    }
+    let excluded = covered.difference(fish) | (covered_twice & fish);
+    if excluded.is_empty() { return None }
+
+    let mut cover_houses = cover_houses.to_vec();
+    cover_houses.sort();
+    Some(StrategyResult::ComplexFish {
+        excluded_candidates: excluded.iter().map(|pos| (pos, val)).collect(),
+        value: val,
+        base_houses: base_houses.to_vec(),
+        cover_houses,
+        mutant,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        solver::tests::{check_example, check_pattern_overlay_equivalence},
+        Pos,
+    };
+
+    #[test]
+    fn test_x_wing_example1() {
+        let line = "..84..5..7......6..329....4....24..342..7..5....8.....2....6..5...2....75.....126";
+        let step_res = StrategyResult::Fish {
+            excluded_candidates: vec![
+                (Pos::new(13), Value::new(4)), (Pos::new(14), Value::new(4)), (Pos::new(49), Value::new(4)),
+                (Pos::new(50), Value::new(4)),
+            ],
+            value: Value::new(4),
+            base_lines: vec![Line::new(2), Line::new(7)],
+            cover_lines: vec![Line::new(13), Line::new(14)],
+        };
+        assert_eq!(step_res.to_string(), "X-Wing: 5 r38 c56 => r2c5,r2c6,r6c5,r6c6<>5");
+        check_example(x_wing, line, Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&Sudoku::from_line(line).unwrap(), Value::new(4), step_res);
+    }
+
+    #[test]
+    fn test_swordfish_example1() {
+        let line = ".39.....4.42.1.3..7..34...9.74.5..3.3..4....5.9...348...3.7.2.14...3..7..175....3";
+        let step_res = StrategyResult::Fish {
+            excluded_candidates: vec![
+                (Pos::new(41), Value::new(8)), (Pos::new(42), Value::new(8)), (Pos::new(57), Value::new(8)),
+                (Pos::new(59), Value::new(8)), (Pos::new(77), Value::new(8)), (Pos::new(78), Value::new(8)),
+            ],
+            value: Value::new(8),
+            base_lines: vec![Line::new(1), Line::new(3), Line::new(7)],
+            cover_lines: vec![Line::new(12), Line::new(14), Line::new(15)],
+        };
+        check_example(x_wing, line, None);
+        assert_eq!(step_res.to_string(), "Swordfish: 9 r248 c467 => r5c6,r5c7,r7c4,r7c6,r9c6,r9c7<>9");
+        check_example(swordfish, line, Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&Sudoku::from_line(line).unwrap(), Value::new(8), step_res);
+    }
+
+    #[test]
+    fn test_jellyfish_example1() {
+        let line = "6784952313.28...455.42..6..7.51.24...6.754.12241.8..5..57.2.1644265.1...1..64.52.";
+        let step_res = StrategyResult::Fish {
+            excluded_candidates: vec![
+                (Pos::new(42), Value::new(8)), (Pos::new(51), Value::new(8)), (Pos::new(53), Value::new(8)),
+                (Pos::new(73), Value::new(8)), (Pos::new(80), Value::new(8)),
+            ],
+            value: Value::new(8),
+            base_lines: vec![Line::new(1), Line::new(2), Line::new(3), Line::new(7)],
+            cover_lines: vec![Line::new(10), Line::new(15), Line::new(16), Line::new(17)],
+        };
+        check_example(x_wing, line, None);
+        check_example(swordfish, line, None);
+        assert_eq!(step_res.to_string(), "Jellyfish: 9 r2348 c2789 => r5c7,r6c7,r6c9,r9c2,r9c9<>9");
+        check_example(jellyfish, line, Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&Sudoku::from_line(line).unwrap(), Value::new(8), step_res);
+    }
+
+    #[test]
+    fn test_finned_x_wing_example1() {
+        let line = "..84..5..7......6..329....4....24..342..7..5....8.....2....6..5...2....75.....126";
+        let step_res = StrategyResult::FinnedFish {
+            excluded_candidates: vec![(Pos::new(29), Value::new(4))],
+            value: Value::new(4),
+            base_lines: vec![Line::new(10), Line::new(12)],
+            cover_lines: vec![Line::new(1), Line::new(3)],
+            fins: vec![Pos::new(46)],
+            sashimi: false,
+        };
+        check_example(finned_x_wing, line, Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&Sudoku::from_line(line).unwrap(), Value::new(4), step_res);
+    }
+
+    #[test]
+    fn test_sashimi_x_wing_example1() {
+        let line = "....7.48148......7.718.46.9.2.5..314.1..9..62.6.....9513.649.78847..19.6...7.8143";
+        let step_res = StrategyResult::FinnedFish {
+            excluded_candidates: vec![(Pos::new(13), Value::new(4))],
+            value: Value::new(4),
+            base_lines: vec![Line::new(14), Line::new(15)],
+            cover_lines: vec![Line::new(1), Line::new(6)],
+            fins: vec![Pos::new(5)],
+            sashimi: true,
+        };
+        check_example(x_wing, line, None);
+        check_example(finned_x_wing, line, Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&Sudoku::from_line(line).unwrap(), Value::new(4), step_res);
+    }
+
+    #[test]
+    fn test_finned_swordfish_example1() {
+        let line = "..84..5..7......6..329....4....24..342..7..5....8.....2....6..5...2....75.....126";
+        let step_res = StrategyResult::FinnedFish {
+            excluded_candidates: vec![(Pos::new(28), Value::new(4))],
+            value: Value::new(4),
+            base_lines: vec![Line::new(2), Line::new(5), Line::new(7)],
+            cover_lines: vec![Line::new(10), Line::new(13), Line::new(14)],
+            fins: vec![Pos::new(47)],
+            sashimi: false,
+        };
+        check_example(finned_swordfish, line, Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&Sudoku::from_line(line).unwrap(), Value::new(4), step_res);
+    }
+
+    #[test]
+    fn test_finned_jellyfish_example1() {
+        let line = "...957.237.9..3..5325...9.7.9....234.734..159542319678..6.3.7.2.3..9.5.6.5.6.83.1";
+        let step_res = StrategyResult::FinnedFish {
+            excluded_candidates: vec![(Pos::new(0), Value::new(5))],
+            value: Value::new(5),
+            base_lines: vec![Line::new(1), Line::new(2), Line::new(3), Line::new(4)],
+            cover_lines: vec![Line::new(9), Line::new(13), Line::new(14), Line::new(16)],
+            fins: vec![Pos::new(10)],
+            sashimi: false,
+        };
+        check_example(finned_x_wing, line, None);
+        check_example(finned_swordfish, line, None);
+        check_example(finned_jellyfish, line, Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&Sudoku::from_line(line).unwrap(), Value::new(5), step_res);
+    }
+
+    const COMPLEX_FISH_EXAMPLE1: &str = "\
+4   8    2   | 7   1    5    | 6    9    3
+9   5    7   | 6   2    3    | 4    8    1
+6   3    1   | 48  49   89   | 5    7    2
+-------------+---------------+------------
+7   26   8   | 9   5    1    | 23   236  4
+23  9    36  | 24  467  267  | 8    1    5
+1   4    5   | 3   8    26   | 9    26   7
+-------------+---------------+------------
+5   1    4   | 28  367  2678 | 237  23   9
+8   27   39  | 5   379  279  | 1    4    6
+23  267  369 | 1   379  4    | 237  5    8";
+
+    const COMPLEX_FISH_EXAMPLE2: &str = "\
+9     6     378  | 178   2    178  | 5   38   4
+18    4     158  | 9     56   3    | 7   268  268
+38    578   2    | 678   4    5678 | 1   9    368
+-----------------+-----------------+--------------
+23    279   379  | 1367  36   1267 | 8   4    5
+4     58    58   | 37    9    27   | 6   237  1
+1236  127   1367 | 5     8    4    | 23  237  9
+-----------------+-----------------+--------------
+5     3     4    | 2     1    68   | 9   68   7
+1268  1289  1689 | 368   7    5689 | 4   15   2368
+7     1289  1689 | 4     356  5689 | 23  15   2368";
+
+    #[test]
+    fn test_franken_fish_example1() {
+        let sudoku = Sudoku::from_pencilmarks(COMPLEX_FISH_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::ComplexFish {
+            excluded_candidates: vec![
+                (Pos::new(41), Value::new(1)), (Pos::new(59), Value::new(1)), (Pos::new(73), Value::new(1)),
+            ],
+            value: Value::new(1),
+            base_houses: vec![House::new(3), House::new(5), House::new(7)],
+            cover_houses: vec![House::new(10), House::new(14), House::new(23)],
+            mutant: false,
+        };
+        assert_eq!(step_res.to_string(), "Franken Swordfish: 2 r468 c26b6 => r5c6,r7c6,r9c2<>2");
+        assert_eq!(franken_fish(&sudoku, 2), None);
+        assert_eq!(franken_fish(&sudoku, 4), Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&sudoku, Value::new(1), step_res);
+    }
+
+    #[test]
+    fn test_franken_fish_example2() {
+        let sudoku = Sudoku::from_pencilmarks(COMPLEX_FISH_EXAMPLE2).unwrap();
+        let step_res = StrategyResult::ComplexFish {
+            excluded_candidates: vec![
+                (Pos::new(30), Value::new(2)), (Pos::new(52), Value::new(2)), (Pos::new(80), Value::new(2)),
+            ],
+            value: Value::new(2),
+            base_houses: vec![House::new(4), House::new(7), House::new(20)],
+            cover_houses: vec![House::new(12), House::new(16), House::new(17)],
+            mutant: false,
+        };
+        assert_eq!(franken_fish(&sudoku, 4), Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&sudoku, Value::new(2), step_res);
+    }
+
+    #[test]
+    fn test_mutant_fish_example1() {
+        let sudoku = Sudoku::from_pencilmarks(COMPLEX_FISH_EXAMPLE2).unwrap();
+        let step_res = StrategyResult::ComplexFish {
+            excluded_candidates: vec![
+                (Pos::new(30), Value::new(2)), (Pos::new(52), Value::new(2)), (Pos::new(80), Value::new(2)),
+            ],
+            value: Value::new(2),
+            base_houses: vec![House::new(4), House::new(7), House::new(15)],
+            cover_houses: vec![House::new(12), House::new(23), House::new(26)],
+            mutant: true,
+        };
+        assert_eq!(step_res.to_string(), "Mutant Swordfish: 3 r58c7 c4b69 => r4c4,r6c8,r9c9<>3");
+        assert_eq!(mutant_fish(&sudoku, 4), Some(step_res.clone()));
+        check_pattern_overlay_equivalence(&sudoku, Value::new(2), step_res);
+    }
+}
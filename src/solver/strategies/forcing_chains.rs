@@ -0,0 +1,415 @@
+use super::{
+    chains::node_idx,
+    hidden_single::hidden_single,
+    locked_candidate::locked_candidate,
+    naked_single::naked_single,
+    trial::{find_contradiction, Contradiction},
+    StrategyResult,
+};
+use crate::{
+    solver::{House, PosBitSet, ValueBitSet},
+    Pos, Sudoku, Value,
+};
+use std::fmt;
+
+/// Which assumptions a forcing chain tries
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ForcingChainKind {
+    /// Each candidate of a bivalue cell
+    Cell,
+    /// Each position of a value in the house
+    Region(House),
+    /// A single candidate, both true and false
+    Digit,
+}
+
+/// One inference in a forcing chain branch
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Implication {
+    /// The step which made the inference, or `None` for the branch's assumption
+    pub step: Option<StrategyResult>,
+    /// Indices of the earlier implications in the branch which the step depends on
+    pub premises: Vec<usize>,
+}
+
+/// An assumption tried by a forcing chain, and what follows from it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForcingChainBranch {
+    pub pos: Pos,
+    pub value: Value,
+    /// Whether the candidate is assumed true, rather than false
+    pub assumed_true: bool,
+    /// The implications the conclusions depend on, starting with the assumption, so the premises
+    /// of each come before it
+    pub implications: Vec<Implication>,
+    /// If the branch is impossible, the implications which leave a cell with no candidates or a
+    /// value with no place in a house
+    pub contradiction: Option<Vec<usize>>,
+}
+
+fn premises_str(premises: &[usize]) -> String {
+    premises.iter().map(|idx| format!("#{}", idx)).collect::<Vec<_>>().join(", ")
+}
+
+/// Lists the implications one per line, each with the implications it follows from, e.g.
+/// `#2 Hidden Single: r4c5=7 in block 5 (#0, #1)`
+impl fmt::Display for ForcingChainBranch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "#0 {}{}{}", self.pos, if self.assumed_true { "=" } else { "<>" }, self.value)?;
+        for (idx, implication) in self.implications.iter().enumerate() {
+            if let Some(step) = &implication.step {
+                write!(f, "\n#{} {} ({})", idx, step, premises_str(&implication.premises))?;
+            }
+        }
+        if let Some(premises) = &self.contradiction {
+            write!(f, "\nContradiction ({})", premises_str(premises))?;
+        }
+        Ok(())
+    }
+}
+
+/// A branch being propagated, recording which implication removed each candidate and placed each
+/// value
+struct Propagation {
+    sudoku: Sudoku,
+    removed_by: Vec<Option<usize>>,
+    placed_by: Vec<Option<usize>>,
+    implications: Vec<Implication>,
+    contradiction: Option<Vec<usize>>,
+}
+
+impl Propagation {
+    fn new(sudoku: &Sudoku, pos: Pos, val: Value, assumed_true: bool) -> Self {
+        let mut ret = Self {
+            sudoku: sudoku.clone(),
+            removed_by: vec![None; Pos::N * Value::N],
+            placed_by: vec![None; Pos::N],
+            implications: vec![Implication { step: None, premises: Vec::new() }],
+            contradiction: None,
+        };
+        if assumed_true {
+            ret.place(pos, val, 0);
+        } else {
+            ret.remove(pos, val, 0);
+        }
+        ret
+    }
+
+    fn place(&mut self, pos: Pos, val: Value, idx: usize) {
+        for val2 in self.sudoku.get_candidates_by_pos(pos).iter().filter(|&val2| val2 != val) {
+            self.removed_by[node_idx(pos, val2)] = Some(idx);
+        }
+        for pos2 in (self.sudoku.get_candidates_by_value(val) & pos.neighbors_bitset()).iter() {
+            self.removed_by[node_idx(pos2, val)] = Some(idx);
+        }
+        self.placed_by[pos.as_usize()] = Some(idx);
+        self.sudoku.set_value(pos, val);
+    }
+
+    fn remove(&mut self, pos: Pos, val: Value, idx: usize) {
+        self.removed_by[node_idx(pos, val)] = Some(idx);
+        self.sudoku.remove_candidate(pos, val);
+    }
+
+    /// The implications which removed any of the candidates, sorted and without duplicates
+    fn removers(&self, candidates: impl Iterator<Item = (Pos, Value)>) -> Vec<usize> {
+        let mut ret: Vec<usize> = candidates.filter_map(|(pos, val)| self.removed_by[node_idx(pos, val)]).collect();
+        ret.sort_unstable();
+        ret.dedup();
+        ret
+    }
+
+    /// The implications whose removed candidates let the step be found
+    fn premises(&self, step: &StrategyResult) -> Vec<usize> {
+        match step {
+            StrategyResult::NakedSingle(pos, val) =>
+                self.removers(Value::iter().filter(|val2| val2 != val).map(|val2| (*pos, val2))),
+            StrategyResult::HiddenSingle(pos, val, house) =>
+                self.removers(house.members_iter().filter(|pos2| pos2 != pos).map(|pos2| (pos2, *val))),
+            StrategyResult::LockedCandidate { value, block, line, pointing, .. } => {
+                let (house, other) = if *pointing {
+                    (block.members_bitset(), line.members_bitset())
+                } else {
+                    (line.members_bitset(), block.members_bitset())
+                };
+                self.removers(house.difference(other).iter().map(|pos| (pos, *value)))
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// The implications leaving an empty cell with no candidates, or a value with nowhere to go in
+    /// a house
+    fn find_contradiction(&self) -> Option<Vec<usize>> {
+        Some(match find_contradiction(&self.sudoku)? {
+            Contradiction::NoCandidates(pos) => self.removers(Value::iter().map(|val| (pos, val))),
+            Contradiction::NoPosition(val, house) => self.removers(house.members_iter().map(|pos| (pos, val))),
+        })
+    }
+
+    /// Applies singles and locked candidates until there are none left, or there's a contradiction
+    fn propagate(&mut self) {
+        loop {
+            if let Some(premises) = self.find_contradiction() {
+                self.contradiction = Some(premises);
+                return
+            }
+            let step = match naked_single(&self.sudoku)
+                .or_else(|| hidden_single(&self.sudoku))
+                .or_else(|| locked_candidate(&self.sudoku))
+            {
+                Some(step) => step,
+                None => return,
+            };
+
+            let idx = self.implications.len();
+            let premises = self.premises(&step);
+            for (pos, val) in step.excluded_candidates() {
+                self.remove(pos, val, idx);
+            }
+            for (pos, val) in step.required_candidates() {
+                self.place(pos, val, idx);
+            }
+            self.implications.push(Implication { step: Some(step), premises });
+        }
+    }
+
+    /// The branch, keeping only the assumption, the implications in `roots` and the contradiction,
+    /// and those they depend on
+    fn into_branch(self, pos: Pos, value: Value, assumed_true: bool, mut roots: Vec<usize>) -> ForcingChainBranch {
+        let mut needed = vec![false; self.implications.len()];
+        needed[0] = true;
+        roots.extend(self.contradiction.iter().flatten());
+        while let Some(idx) = roots.pop() {
+            if !needed[idx] {
+                needed[idx] = true;
+                roots.extend(&self.implications[idx].premises);
+            }
+        }
+
+        // Renumber the implications which are kept
+        let mut new_idx = vec![0; needed.len()];
+        let mut cnt = 0;
+        for (idx, &is_needed) in needed.iter().enumerate() {
+            new_idx[idx] = cnt;
+            if is_needed { cnt += 1 }
+        }
+        let renumber = |premises: &[usize]| premises.iter().map(|&idx| new_idx[idx]).collect();
+
+        ForcingChainBranch {
+            pos, value, assumed_true,
+            contradiction: self.contradiction.as_deref().map(renumber),
+            implications: self.implications.iter().zip(needed)
+                .filter(|&(_, is_needed)| is_needed)
+                .map(|(implication, _)| Implication { step: implication.step.clone(), premises: renumber(&implication.premises) })
+                .collect(),
+        }
+    }
+}
+
+/// Propagates each assumption, one of which must be true. Whatever follows from every branch is
+/// true, unless some branches lead to a contradiction, in which case the conclusion is only that
+/// their assumptions are false.
+fn forcing_chain(sudoku: &Sudoku, kind: ForcingChainKind, assumptions: &[(Pos, Value, bool)]) -> Option<StrategyResult> {
+    let propagations: Vec<Propagation> = assumptions.iter()
+        .map(|&(pos, val, assumed_true)| {
+            let mut propagation = Propagation::new(sudoku, pos, val, assumed_true);
+            propagation.propagate();
+            propagation
+        })
+        .collect();
+    let contradictions = propagations.iter().filter(|propagation| propagation.contradiction.is_some()).count();
+    // Every branch failing means the sudoku has no solution, which isn't for a strategy to find
+    if contradictions == propagations.len() { return None }
+
+    let mut excluded_candidates = Vec::new();
+    let mut required_candidates = Vec::new();
+    if contradictions > 0 {
+        for (&(pos, val, assumed_true), propagation) in assumptions.iter().zip(&propagations) {
+            if propagation.contradiction.is_none() { continue }
+            if assumed_true {
+                excluded_candidates.push((pos, val));
+            } else {
+                required_candidates.push((pos, val));
+            }
+        }
+    } else {
+        for pos in Pos::iter().filter(|&pos| sudoku.get_value(pos).is_none()) {
+            if let Some(val) = propagations[0].sudoku.get_value(pos) {
+                if propagations.iter().all(|propagation| propagation.sudoku.get_value(pos) == Some(val)) {
+                    required_candidates.push((pos, val));
+                }
+            }
+        }
+        // Leave out candidates which the required values would exclude anyway
+        let mut implied = [ValueBitSet::NONE; Pos::N];
+        for &(pos, val) in &required_candidates {
+            implied[pos.as_usize()] = ValueBitSet::ALL;
+            for pos2 in pos.neighbors_bitset().iter() {
+                implied[pos2.as_usize()].insert(val);
+            }
+        }
+        for pos in Pos::iter() {
+            for val in sudoku.get_candidates_by_pos(pos).difference(implied[pos.as_usize()]).iter() {
+                if propagations.iter().all(|propagation| propagation.removed_by[node_idx(pos, val)].is_some()) {
+                    excluded_candidates.push((pos, val));
+                }
+            }
+        }
+    }
+    if excluded_candidates.is_empty() && required_candidates.is_empty() { return None }
+
+    let branches = assumptions.iter().zip(propagations)
+        .map(|(&(pos, val, assumed_true), propagation)| {
+            let roots = if contradictions > 0 {
+                Vec::new()
+            } else {
+                required_candidates.iter().map(|&(pos, _)| propagation.placed_by[pos.as_usize()].unwrap())
+                    .chain(excluded_candidates.iter().map(|&(pos, val)| propagation.removed_by[node_idx(pos, val)].unwrap()))
+                    .collect()
+            };
+            propagation.into_branch(pos, val, assumed_true, roots)
+        })
+        .collect();
+    Some(StrategyResult::ForcingChain { excluded_candidates, required_candidates, kind, branches })
+}
+
+/// Each candidate of a bivalue cell is tried in turn
+pub(crate) fn cell_forcing_chain(sudoku: &Sudoku) -> Option<StrategyResult> {
+    for pos in Pos::iter() {
+        let candidates = sudoku.get_candidates_by_pos(pos);
+        if candidates.len() != 2 { continue }
+
+        let assumptions: Vec<_> = candidates.iter().map(|val| (pos, val, true)).collect();
+        if let ret@Some(_) = forcing_chain(sudoku, ForcingChainKind::Cell, &assumptions) { return ret }
+    }
+    None
+}
+
+/// Each position of a value in a house is tried in turn
+pub(crate) fn region_forcing_chain(sudoku: &Sudoku) -> Option<StrategyResult> {
+    for house in House::iter() {
+        for val in Value::iter() {
+            let positions: PosBitSet = sudoku.get_candidates_by_value(val) & house.members_bitset();
+            if positions.len() < 2 { continue }
+
+            let assumptions: Vec<_> = positions.iter().map(|pos| (pos, val, true)).collect();
+            if let ret@Some(_) = forcing_chain(sudoku, ForcingChainKind::Region(house), &assumptions) { return ret }
+        }
+    }
+    None
+}
+
+/// Each candidate is tried as both true and false
+pub(crate) fn digit_forcing_chain(sudoku: &Sudoku) -> Option<StrategyResult> {
+    for pos in Pos::iter() {
+        for val in sudoku.get_candidates_by_pos(pos).iter() {
+            let assumptions = [(pos, val, true), (pos, val, false)];
+            if let ret@Some(_) = forcing_chain(sudoku, ForcingChainKind::Digit, &assumptions) { return ret }
+        }
+    }
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORCING_CHAIN_EXAMPLE1: &str = "\
+156  4     3    | 2    56   9  | 7    8   16
+2    679   179  | 137  137  8  | 4    69  5
+8    5679  1579 | 17   56   4  | 19   3   2
+----------------+--------------+-------------
+67   2     68   | 5    4    37 | 38   1   9
+17   38    4    | 9    17   2  | 6    5   38
+159  359   159  | 6    8    13 | 2    4   7
+----------------+--------------+-------------
+45   58    2    | 134  9    6  | 135  7   138
+49   1     689  | 347  37   57 | 58   2   36
+3    567   567  | 8    2    15 | 159  69  4";
+
+    const FORCING_CHAIN_EXAMPLE2: &str = "\
+5    7    48  | 2      9      1    | 48   3    6
+1    9    248 | 36     36     478  | 478  5    278
+6    3    248 | 478    78     5    | 9    278  1
+--------------+--------------------+--------------
+29   6    1   | 789    278    3    | 78   4    5
+249  5    7   | 14689  1268   2489 | 3    268  289
+8    24   3   | 5      267    2479 | 1    267  279
+--------------+--------------------+--------------
+347  48   59  | 3789   3578   6    | 2    1    378
+237  128  59  | 13789  12358  2789 | 6    78   4
+237  128  6   | 1378   4      278  | 5    9    378";
+
+    fn forcing_chain_branches(step_res: StrategyResult) -> Vec<ForcingChainBranch> {
+        match step_res {
+            StrategyResult::ForcingChain { branches, .. } => branches,
+            _ => panic!("Expected a forcing chain, got {}", step_res),
+        }
+    }
+
+    #[test]
+    fn test_cell_forcing_chain_example1() {
+        let sudoku = Sudoku::from_pencilmarks(FORCING_CHAIN_EXAMPLE1).unwrap();
+        let step_res = cell_forcing_chain(&sudoku).unwrap();
+        assert_eq!(step_res.to_string(), "Cell Forcing Chain: r1c5=5/6 => r1c5<>6");
+        let branches = forcing_chain_branches(step_res);
+        // Only the contradiction needs explaining
+        assert_eq!(branches[0].to_string(), "#0 r1c5=5");
+        assert_eq!(branches[1].to_string(), "\
+#0 r1c5=6
+#1 Naked Single: r1c9=1 (#0)
+#2 Naked Single: r1c1=5 (#0, #1)
+#3 Naked Single: r7c1=4 (#2)
+#4 Naked Single: r8c1=9 (#3)
+#5 Naked Single: r6c1=1 (#2, #4)
+#6 Naked Single: r5c1=7 (#5)
+#7 Naked Single: r4c1=6 (#6)
+#8 Naked Single: r4c3=8 (#7)
+#9 Naked Single: r4c7=3 (#8)
+#10 Naked Single: r5c9=8 (#9)
+#11 Naked Single: r7c9=3 (#1, #10)
+#12 Naked Single: r7c4=1 (#3, #11)
+Contradiction (#5, #12)");
+    }
+
+    #[test]
+    fn test_region_forcing_chain_example1() {
+        let sudoku = Sudoku::from_pencilmarks(FORCING_CHAIN_EXAMPLE1).unwrap();
+        let step_res = region_forcing_chain(&sudoku).unwrap();
+        assert_eq!(step_res.to_string(), "Region Forcing Chain: 1 in row 1 at r1c1,r1c9 => r3c2<>9");
+        let branches = forcing_chain_branches(step_res);
+        assert!(branches.iter().all(|branch| branch.contradiction.is_none()));
+        assert_eq!(branches[0].implications.len(), 14);
+        assert_eq!(branches[1].to_string(), "#0 r1c9=1\n#1 Naked Single: r3c7=9 (#0)");
+    }
+
+    #[test]
+    fn test_region_forcing_chain_example2() {
+        let sudoku = Sudoku::from_pencilmarks(FORCING_CHAIN_EXAMPLE2).unwrap();
+        let step_res = region_forcing_chain(&sudoku).unwrap();
+        assert_eq!(step_res.to_string(), "Region Forcing Chain: 4 in row 2 at r2c3,r2c6,r2c7 => r2c6<>4");
+    }
+
+    #[test]
+    fn test_digit_forcing_chain_example1() {
+        let sudoku = Sudoku::from_pencilmarks(FORCING_CHAIN_EXAMPLE1).unwrap();
+        let step_res = digit_forcing_chain(&sudoku).unwrap();
+        assert_eq!(step_res.to_string(), "Digit Forcing Chain: 1 at r1c1 => r3c2<>9");
+        let branches = forcing_chain_branches(step_res);
+        assert!(!branches[1].assumed_true);
+        assert_eq!(branches[1].to_string(), "\
+#0 r1c1<>1
+#1 Hidden Single: r1c9=1 in row 1 (#0)
+#2 Naked Single: r3c7=9 (#1)");
+    }
+
+    #[test]
+    fn test_digit_forcing_chain_example2() {
+        let sudoku = Sudoku::from_pencilmarks(FORCING_CHAIN_EXAMPLE2).unwrap();
+        let step_res = digit_forcing_chain(&sudoku).unwrap();
+        assert_eq!(step_res.to_string(), "Digit Forcing Chain: 8 at r2c3 => r2c3<>8");
+        assert_eq!(step_res.strategy(), Some(crate::solver::Strategy::DigitForcingChain));
+    }
+}
@@ -15,14 +15,15 @@ pub(crate) fn guess_and_check(sudoku: &Sudoku, mut initial_steps: Vec<StrategyRe
     for val in sudoku.get_candidates_by_pos(pos).iter() {
         let mut sudoku2 = sudoku.clone();
         sudoku2.set_value(pos, val);
-        res = res.merge(solve(sudoku2, &SolveOpts::fast()));
-        if let SolveSuccess::NonUnique = res.success {
-            initial_steps.push(StrategyResult::GuessAndCheck(pos, val));
-            initial_steps.append(&mut res.steps);
-            res.steps = initial_steps;
-            return res;
+        let mut guess_res = solve(sudoku2, &SolveOpts::fast());
+        if !guess_res.is_unsolvable() {
+            // Record the guess that led to the solution we keep
+            guess_res.steps.insert(0, StrategyResult::GuessAndCheck(pos, val));
         }
+        res = res.merge(guess_res);
+        if let SolveSuccess::NonUnique = res.success { break }
     }
+    initial_steps.append(&mut res.steps);
     res.steps = initial_steps;
     res
 }
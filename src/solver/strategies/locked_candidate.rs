@@ -1,47 +1,50 @@
 use super::StrategyResult;
 use crate::{
-    solver::Block,
+    solver::{Block, Line, PosBitSet},
     Sudoku, Value,
 };
 
+/// The candidates in each intersection of a block and a line, skipping empty intersections
+pub(crate) fn intersections(candidates: PosBitSet) -> impl Iterator<Item = (Block, Line, PosBitSet)> {
+    Block::iter()
+        .flat_map(move |block| block.intersecting_lines_iter()
+            .map(move |line| (block, line, candidates & block.members_bitset() & line.members_bitset())))
+        .filter(|(_, _, intersection_candidates)| !intersection_candidates.is_empty())
+}
+
 pub(crate) fn locked_candidate(sudoku: &Sudoku) -> Option<StrategyResult> {
     for val in Value::iter() {
         let all_candidates = sudoku.get_candidates_by_value(val);
         if all_candidates.is_empty() { continue }
 
-        for block in Block::iter() {
+        for (block, line, intersection_candidates) in intersections(all_candidates) {
             let block_candidates = all_candidates & block.members_bitset();
-            if block_candidates.is_empty() { continue }
-            for line in block.intersecting_lines_iter() {
-                let line_candidates = all_candidates & line.members_bitset();
-                let intersection_candidates = block_candidates & line_candidates;
-                if intersection_candidates.is_empty() { continue }
+            let line_candidates = all_candidates & line.members_bitset();
 
-                // Type 1 - pointing
-                if block_candidates.difference(intersection_candidates).is_empty() &&
-                    !line_candidates.difference(intersection_candidates).is_empty()
-                {
-                    return Some(StrategyResult::LockedCandidate {
-                        value: val,
-                        excluded_positions: line_candidates.difference(intersection_candidates).iter().collect(),
-                        positions: intersection_candidates.iter().collect(),
-                        block, line,
-                        pointing: true
-                    });
-                }
+            // Type 1 - pointing
+            if block_candidates.difference(intersection_candidates).is_empty() &&
+                !line_candidates.difference(intersection_candidates).is_empty()
+            {
+                return Some(StrategyResult::LockedCandidate {
+                    value: val,
+                    excluded_positions: line_candidates.difference(intersection_candidates).iter().collect(),
+                    positions: intersection_candidates.iter().collect(),
+                    block, line,
+                    pointing: true
+                });
+            }
 
-                // Type 2 - claiming
-                if line_candidates.difference(intersection_candidates).is_empty() &&
-                    !block_candidates.difference(intersection_candidates).is_empty()
-                {
-                    return Some(StrategyResult::LockedCandidate {
-                        value: val,
-                        excluded_positions: block_candidates.difference(intersection_candidates).iter().collect(),
-                        positions: intersection_candidates.iter().collect(),
-                        block, line,
-                        pointing: false
-                    });
-                }
+            // Type 2 - claiming
+            if line_candidates.difference(intersection_candidates).is_empty() &&
+                !block_candidates.difference(intersection_candidates).is_empty()
+            {
+                return Some(StrategyResult::LockedCandidate {
+                    value: val,
+                    excluded_positions: block_candidates.difference(intersection_candidates).iter().collect(),
+                    positions: intersection_candidates.iter().collect(),
+                    block, line,
+                    pointing: false
+                });
             }
         }
     }
@@ -2,8 +2,17 @@ use crate::{
     solver::{Line, House, Block, PosBitSet, ValueBitSet},
     Pos, Value,
 };
+use std::{
+    fmt,
+    str::FromStr,
+};
 
+mod als;
+mod chains;
 mod coloring;
+mod exocet;
+mod fish;
+mod forcing_chains;
 mod guess_and_check;
 mod hidden_single;
 mod hidden_subset;
@@ -11,9 +20,20 @@ mod locked_candidate;
 mod naked_single;
 mod naked_subset;
 mod pattern_overlay;
+mod single_digit;
+mod sue_de_coq;
+mod trial;
+mod uniqueness;
 mod wings;
 
+pub(crate) use als::{als_xz, als_xy_wing, death_blossom, AlmostLockedSet};
+pub(crate) use chains::{aic, x_chain, xy_chain, Chain, GroupedChain};
 pub(crate) use coloring::{multi_color, simple_color, Coloring};
+pub(crate) use exocet::junior_exocet;
+pub(crate) use fish::{
+    x_wing, swordfish, jellyfish, finned_x_wing, finned_swordfish, finned_jellyfish, franken_fish, mutant_fish,
+};
+pub(crate) use forcing_chains::{cell_forcing_chain, region_forcing_chain, digit_forcing_chain};
 pub(crate) use guess_and_check::guess_and_check;
 pub(crate) use hidden_single::hidden_single;
 pub(crate) use hidden_subset::{hidden_pair, hidden_triple, hidden_quadruple};
@@ -21,17 +41,51 @@ pub(crate) use locked_candidate::locked_candidate;
 pub(crate) use naked_single::naked_single;
 pub(crate) use naked_subset::{naked_pair, naked_triple, naked_quadruple};
 pub(crate) use pattern_overlay::pattern_overlay;
-pub(crate) use wings::{xy_wing, xyz_wing, wxyz_wing};
+pub(crate) use single_digit::{skyscraper, two_string_kite, turbot_fish, empty_rectangle};
+pub(crate) use sue_de_coq::sue_de_coq;
+pub(crate) use trial::trial;
+pub(crate) use uniqueness::{unique_rectangle, hidden_unique_rectangle, avoidable_rectangle, bivalue_universal_grave};
+pub(crate) use wings::{xy_wing, xyz_wing, wxyz_wing, wing, w_wing, remote_pair, MAX_WING_SIZE};
+
+pub use chains::{AicKind, LinkType};
+pub use forcing_chains::{ForcingChainBranch, ForcingChainKind, Implication};
+pub use single_digit::SingleDigitPatternKind;
+pub use trial::Contradiction;
+pub use uniqueness::UniqueRectangleKind;
 
 #[cfg(test)]
 pub(crate) use pattern_overlay::pattern_overlay_for_value;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Strategy {
+    Aic {
+        /// The maximum number of nodes in the chain
+        max_length: usize,
+        /// Whether nodes can be groups of candidates in a block and line intersection
+        grouped: bool,
+        /// Whether nodes can be almost locked sets
+        als: bool
+    },
+    AlsXyWing,
+    AlsXz,
+    AvoidableRectangle,
+    BivalueUniversalGrave,
+    CellForcingChain,
+    DeathBlossom,
+    DigitForcingChain,
+    EmptyRectangle,
+    FinnedJellyfish,
+    FinnedSwordfish,
+    FinnedXWing,
+    /// The maximum number of base houses
+    FrankenFish(usize),
     HiddenPair,
     HiddenQuadruple,
     HiddenSingle,
     HiddenTriple,
+    HiddenUniqueRectangle,
+    Jellyfish,
+    JuniorExocet,
     LockedCandidate,
     NakedPair,
     NakedQuadruple,
@@ -39,13 +93,135 @@ pub enum Strategy {
     NakedTriple,
     /// The maximum number of pairs of colors to allow in the chain
     MultiColor(usize),
+    /// The maximum number of base houses
+    MutantFish(usize),
     PatternOverlay,
+    RegionForcingChain,
+    RemotePair,
     SimpleColor,
+    Skyscraper,
+    SueDeCoq,
+    Swordfish,
+    Trial {
+        /// The most steps to take after the assumption
+        max_steps: usize,
+        /// The strategies to take them with
+        strategies: &'static [Strategy]
+    },
+    TurbotFish,
+    TwoStringKite,
+    UniqueRectangle,
+    /// The maximum number of cells
+    Wing(usize),
+    /// The maximum number of candidates in the chain
+    XChain(usize),
+    XWing,
+    /// The maximum number of candidates in the chain, two for each cell
+    XyChain(usize),
     XyWing,
     XyzWing,
+    WWing,
     WxyzWing,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StrategyParseError {
+    UnknownStrategy(String),
+    InvalidParameter(String),
+}
+
+/// Parses a strategy by its name, e.g. `NakedPair` or `MultiColor(3)`. Names are case
+/// insensitive, and parameters are optional (defaulting to the value used in [`ALL`]).
+impl FromStr for Strategy {
+    type Err = StrategyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (name, param) = match s.find('(') {
+            Some(idx) if s.ends_with(')') => (&s[..idx], Some(s[idx + 1 .. s.len() - 1].trim())),
+            Some(_) => return Err(StrategyParseError::InvalidParameter(s.to_string())),
+            None => (s, None)
+        };
+        let parse_usize = |default: usize| -> Result<usize, StrategyParseError> {
+            match param {
+                None => Ok(default),
+                Some(param) => param.parse().map_err(|_| StrategyParseError::InvalidParameter(s.to_string()))
+            }
+        };
+        let no_param = |strat: Strategy| -> Result<Strategy, StrategyParseError> {
+            match param {
+                None => Ok(strat),
+                Some(_) => Err(StrategyParseError::InvalidParameter(s.to_string()))
+            }
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "aic" => Ok(Strategy::Aic { max_length: parse_usize(usize::MAX)?, grouped: false, als: false }),
+            "alsaic" => Ok(Strategy::Aic { max_length: parse_usize(usize::MAX)?, grouped: true, als: true }),
+            "alsxywing" => no_param(Strategy::AlsXyWing),
+            "alsxz" => no_param(Strategy::AlsXz),
+            "avoidablerectangle" => no_param(Strategy::AvoidableRectangle),
+            "bivalueuniversalgrave" | "bug" => no_param(Strategy::BivalueUniversalGrave),
+            "cellforcingchain" => no_param(Strategy::CellForcingChain),
+            "deathblossom" => no_param(Strategy::DeathBlossom),
+            "digitforcingchain" => no_param(Strategy::DigitForcingChain),
+            "emptyrectangle" => no_param(Strategy::EmptyRectangle),
+            "finnedjellyfish" => no_param(Strategy::FinnedJellyfish),
+            "finnedswordfish" => no_param(Strategy::FinnedSwordfish),
+            "finnedxwing" => no_param(Strategy::FinnedXWing),
+            "frankenfish" => Ok(Strategy::FrankenFish(parse_usize(4)?)),
+            "groupedaic" => Ok(Strategy::Aic { max_length: parse_usize(usize::MAX)?, grouped: true, als: false }),
+            "hiddenpair" => no_param(Strategy::HiddenPair),
+            "hiddenquadruple" => no_param(Strategy::HiddenQuadruple),
+            "hiddensingle" => no_param(Strategy::HiddenSingle),
+            "hiddentriple" => no_param(Strategy::HiddenTriple),
+            "hiddenuniquerectangle" => no_param(Strategy::HiddenUniqueRectangle),
+            "jellyfish" => no_param(Strategy::Jellyfish),
+            "juniorexocet" | "je" => no_param(Strategy::JuniorExocet),
+            "lockedcandidate" => no_param(Strategy::LockedCandidate),
+            "nakedpair" => no_param(Strategy::NakedPair),
+            "nakedquadruple" => no_param(Strategy::NakedQuadruple),
+            "nakedsingle" => no_param(Strategy::NakedSingle),
+            "nakedtriple" => no_param(Strategy::NakedTriple),
+            "multicolor" => Ok(Strategy::MultiColor(parse_usize(usize::MAX)?)),
+            "mutantfish" => Ok(Strategy::MutantFish(parse_usize(4)?)),
+            "patternoverlay" => no_param(Strategy::PatternOverlay),
+            "regionforcingchain" | "houseforcingchain" => no_param(Strategy::RegionForcingChain),
+            "remotepair" | "remotepairs" => no_param(Strategy::RemotePair),
+            "simplecolor" => no_param(Strategy::SimpleColor),
+            "skyscraper" => no_param(Strategy::Skyscraper),
+            "suedecoq" => no_param(Strategy::SueDeCoq),
+            "swordfish" => no_param(Strategy::Swordfish),
+            "trial" | "bowmansbingo" => Ok(Strategy::Trial { max_steps: parse_usize(20)?, strategies: TRIAL }),
+            "turbotfish" => no_param(Strategy::TurbotFish),
+            "twostringkite" | "2stringkite" => no_param(Strategy::TwoStringKite),
+            "uniquerectangle" => no_param(Strategy::UniqueRectangle),
+            "uvwxyzwing" => no_param(Strategy::Wing(6)),
+            "vwxyzwing" => no_param(Strategy::Wing(5)),
+            "wing" => Ok(Strategy::Wing(parse_usize(MAX_WING_SIZE)?)),
+            "xchain" => Ok(Strategy::XChain(parse_usize(usize::MAX)?)),
+            "xwing" => no_param(Strategy::XWing),
+            "xychain" => Ok(Strategy::XyChain(parse_usize(usize::MAX)?)),
+            "xywing" => no_param(Strategy::XyWing),
+            "xyzwing" => no_param(Strategy::XyzWing),
+            "wwing" => no_param(Strategy::WWing),
+            "wxyzwing" => no_param(Strategy::WxyzWing),
+            _ => Err(StrategyParseError::UnknownStrategy(name.to_string()))
+        }
+    }
+}
+
+impl Strategy {
+    /// Parses `ALL`, `FAST`, or a comma separated list of strategies, e.g.
+    /// `NakedSingle,HiddenSingle,MultiColor(3)`
+    pub fn parse_list(s: &str) -> Result<Vec<Strategy>, StrategyParseError> {
+        match s.trim() {
+            "ALL" => Ok(ALL.to_vec()),
+            "FAST" => Ok(FAST.to_vec()),
+            s => s.split(',').map(str::parse).collect()
+        }
+    }
+}
+
 // TODO - benchmark and figure out which is the fastest order and which are worthwhile
 pub const FAST: &'static [Strategy] = &[
     Strategy::NakedSingle,
@@ -56,26 +232,70 @@ pub const FAST: &'static [Strategy] = &[
     Strategy::NakedQuadruple,
 ];
 
+/// The strategies a trial propagates its assumption with, unless others are chosen
+pub const TRIAL: &'static [Strategy] = &[
+    Strategy::NakedSingle,
+    Strategy::HiddenSingle,
+    Strategy::LockedCandidate,
+];
+
 pub const ALL: &'static [Strategy] = &[
     Strategy::NakedSingle,
     Strategy::HiddenSingle,
     Strategy::LockedCandidate,
     Strategy::NakedPair,
     Strategy::HiddenPair,
+    Strategy::XWing,
+    // Named patterns come before the fish and chains which would also find them
+    Strategy::RemotePair,
+    Strategy::Skyscraper,
+    Strategy::TwoStringKite,
+    Strategy::TurbotFish,
+    Strategy::EmptyRectangle,
+    Strategy::FinnedXWing,
     Strategy::NakedTriple,
     Strategy::HiddenTriple,
+    Strategy::Swordfish,
+    Strategy::FinnedSwordfish,
     Strategy::NakedQuadruple,
     Strategy::HiddenQuadruple,
+    Strategy::Jellyfish,
+    Strategy::FinnedJellyfish,
+    Strategy::WWing,
     Strategy::XyWing,
     Strategy::XyzWing,
     Strategy::WxyzWing,
+    // Larger wings are rare, and much slower to search for
+    Strategy::Wing(6),
+    Strategy::SueDeCoq,
+    Strategy::UniqueRectangle,
+    Strategy::AvoidableRectangle,
+    Strategy::HiddenUniqueRectangle,
+    Strategy::BivalueUniversalGrave,
     Strategy::SimpleColor,
     Strategy::MultiColor(usize::MAX),
+    Strategy::XChain(usize::MAX),
+    Strategy::XyChain(usize::MAX),
+    Strategy::Aic { max_length: usize::MAX, grouped: false, als: false },
+    Strategy::Aic { max_length: usize::MAX, grouped: true, als: false },
+    Strategy::AlsXz,
+    Strategy::AlsXyWing,
+    Strategy::DeathBlossom,
+    Strategy::Aic { max_length: usize::MAX, grouped: true, als: true },
+    Strategy::FrankenFish(4),
+    Strategy::MutantFish(4),
+    Strategy::JuniorExocet,
     Strategy::PatternOverlay,
+    Strategy::Trial { max_steps: 20, strategies: TRIAL },
+    Strategy::CellForcingChain,
+    Strategy::RegionForcingChain,
+    Strategy::DigitForcingChain,
 ];
 
+/// A single step taken while solving, recording which strategy made progress and why
 #[derive(Clone, Debug, PartialEq, Eq)]
-pub(crate) enum StrategyResult {
+#[non_exhaustive]
+pub enum StrategyResult {
     NakedSingle(Pos, Value),
     HiddenSingle(Pos, Value, House),
     GuessAndCheck(Pos, Value),
@@ -103,6 +323,35 @@ pub(crate) enum StrategyResult {
         values: Vec<Value>,
         house: House
     },
+    /// A basic fish, whose size is the number of base lines: an X-Wing has 2, a Swordfish 3 and a
+    /// Jellyfish 4. [`StrategyResult::strategy`] and the displayed name follow from this.
+    Fish {
+        excluded_candidates: Vec<(Pos, Value)>,
+        value: Value,
+        /// All rows or all columns, each containing the value in only the cover lines
+        base_lines: Vec<Line>,
+        cover_lines: Vec<Line>
+    },
+    /// A fish with extra candidates in the base lines, all in one block
+    FinnedFish {
+        excluded_candidates: Vec<(Pos, Value)>,
+        value: Value,
+        base_lines: Vec<Line>,
+        cover_lines: Vec<Line>,
+        /// The candidates of the base lines outside the cover lines
+        fins: Vec<Pos>,
+        /// Whether the fish would be degenerate without the fins, i.e. some base line only has one
+        /// candidate in the cover lines
+        sashimi: bool
+    },
+    /// A fish with blocks in the base or cover houses (franken), or any mix of houses (mutant)
+    ComplexFish {
+        excluded_candidates: Vec<(Pos, Value)>,
+        value: Value,
+        base_houses: Vec<House>,
+        cover_houses: Vec<House>,
+        mutant: bool
+    },
     XyWing {
         excluded_candidates: Vec<(Pos, Value)>,
         /// Positions as [xy, xz, yz]
@@ -124,6 +373,44 @@ pub(crate) enum StrategyResult {
         /// Values as [w, x, y, z]
         values: [Value; 4]
     },
+    /// Cells with as many candidates as cells, where only z isn't restricted to one of the cells
+    Wing {
+        excluded_candidates: Vec<(Pos, Value)>,
+        positions: Vec<Pos>,
+        /// The restricted values, then z
+        values: Vec<Value>
+    },
+    /// Two cells with the same two candidates, joined by a strong link on one of them
+    WWing {
+        excluded_candidates: Vec<(Pos, Value)>,
+        positions: [Pos; 2],
+        /// The value of the strong link, then the excluded value
+        values: [Value; 2],
+        /// The strong link, starting from the end which sees the first cell
+        link: [Pos; 2]
+    },
+    /// A chain of cells with the same two candidates, each seeing the next
+    RemotePair {
+        excluded_candidates: Vec<(Pos, Value)>,
+        values: [Value; 2],
+        chain: Vec<Pos>
+    },
+    /// Any of the unique rectangle types, including hidden and avoidable rectangles
+    UniqueRectangle {
+        excluded_candidates: Vec<(Pos, Value)>,
+        /// Positions of the corners, as [top left, top right, bottom left, bottom right]
+        positions: [Pos; 4],
+        /// The deadly pair
+        values: [Value; 2],
+        kind: UniqueRectangleKind
+    },
+    /// BUG+n, where the sudoku would have multiple solutions without any of the extra candidates
+    BivalueUniversalGrave {
+        excluded_candidates: Vec<(Pos, Value)>,
+        /// The extra candidate, if there was only one
+        required_candidates: Vec<(Pos, Value)>,
+        extra_candidates: Vec<(Pos, Value)>
+    },
     SimpleColor {
         excluded_candidates: Vec<(Pos, Value)>,
         value: Value,
@@ -138,16 +425,133 @@ pub(crate) enum StrategyResult {
         value: Value,
         color_positions: Vec<[Vec<Pos>; 2]>
     },
+    /// A chain of a single value, alternating between strong and weak links
+    XChain {
+        excluded_candidates: Vec<(Pos, Value)>,
+        /// Each candidate in the chain, with the type of link to the following candidate. Chains
+        /// start and end with a strong link, so the last candidate's link is weak, back to the
+        /// first through the excluded candidates.
+        chain: Chain
+    },
+    /// A chain of bivalue cells, with strong links inside each cell and weak links between them
+    XyChain {
+        excluded_candidates: Vec<(Pos, Value)>,
+        /// Each candidate in the chain, with the type of link to the following candidate, as for
+        /// `XChain`
+        chain: Chain
+    },
+    /// An alternating inference chain or nice loop, mixing links of any kind
+    Aic {
+        excluded_candidates: Vec<(Pos, Value)>,
+        /// The candidate at both ends of a discontinuous loop
+        required_candidates: Vec<(Pos, Value)>,
+        /// Each node in the chain, with the type of link to the following node. For a continuous
+        /// loop, the last node's link closes the loop.
+        chain: GroupedChain,
+        kind: AicKind
+    },
+    /// Two almost locked sets with restricted common candidates, values which can only be in one
+    /// of the sets
+    AlsXz {
+        excluded_candidates: Vec<(Pos, Value)>,
+        sets: [Vec<Pos>; 2],
+        /// One restricted common candidate, or two if the sets are doubly linked
+        restricted_commons: Vec<Value>
+    },
+    /// Two almost locked sets, each with a different restricted common candidate to a third
+    AlsXyWing {
+        excluded_candidates: Vec<(Pos, Value)>,
+        /// The two outer sets, followed by the set linking them
+        sets: [Vec<Pos>; 3],
+        /// The restricted common candidates between each outer set and the linking set
+        restricted_commons: [Value; 2]
+    },
+    /// A stem cell with an almost locked set for each of its candidates, linked by that candidate
+    DeathBlossom {
+        excluded_candidates: Vec<(Pos, Value)>,
+        stem: Pos,
+        /// Each of the stem's candidates, with the cells of its set
+        petals: Vec<(Value, Vec<Pos>)>
+    },
+    /// Two strong links of a value, with one end of each seeing each other
+    SingleDigitPattern {
+        excluded_candidates: Vec<(Pos, Value)>,
+        value: Value,
+        /// The ends of the first strong link, then of the second, so the middle two see each other
+        positions: [Pos; 4],
+        kind: SingleDigitPatternKind
+    },
+    /// A value in a block confined to one row and one column, and a strong link from one of them
+    EmptyRectangle {
+        excluded_candidates: Vec<(Pos, Value)>,
+        value: Value,
+        block: Block,
+        /// The positions of the value in the block
+        positions: Vec<Pos>,
+        /// The strong link, starting from the end which sees the block
+        link: [Pos; 2]
+    },
+    /// Cells in a block and line intersection, locked together with cells in the rest of the
+    /// line and the rest of the block
+    SueDeCoq {
+        excluded_candidates: Vec<(Pos, Value)>,
+        block: Block,
+        line: Line,
+        /// The cells in the intersection
+        positions: Vec<Pos>,
+        line_positions: Vec<Pos>,
+        block_positions: Vec<Pos>
+    },
+    /// Two base cells in a block and line intersection, whose values must be in the two target
+    /// cells, because the cross-lines leave nowhere else for them
+    JuniorExocet {
+        excluded_candidates: Vec<(Pos, Value)>,
+        base: [Pos; 2],
+        targets: [Pos; 2],
+        /// The candidates of the base cells
+        values: Vec<Value>,
+        /// The lines crossing the band through the targets and the rest of the base intersection
+        cross_lines: [Line; 3]
+    },
     PatternOverlay {
         excluded_candidates: Vec<(Pos, Value)>,
         required_candidates: Vec<(Pos, Value)>,
         value: Value,
         remaining_patterns: usize
     },
+    /// Assuming the candidate leads to a contradiction within a few steps
+    Trial {
+        pos: Pos,
+        value: Value,
+        /// The strategies used after the assumption
+        strategies: &'static [Strategy],
+        /// The steps taken after the assumption, in order
+        steps: Vec<StrategyResult>,
+        contradiction: Contradiction
+    },
+    /// Assumptions, one of which must be true, that either lead to the same conclusions or to a
+    /// contradiction
+    ForcingChain {
+        excluded_candidates: Vec<(Pos, Value)>,
+        required_candidates: Vec<(Pos, Value)>,
+        kind: ForcingChainKind,
+        branches: Vec<ForcingChainBranch>
+    },
+}
+
+impl Strategy {
+    /// Whether the strategy assumes the sudoku has a unique solution, so can give wrong results
+    /// for sudokus that don't
+    pub fn requires_uniqueness(&self) -> bool {
+        matches!(self,
+            Strategy::UniqueRectangle | Strategy::HiddenUniqueRectangle | Strategy::AvoidableRectangle |
+            Strategy::BivalueUniversalGrave)
+    }
 }
 
 impl StrategyResult {
-    pub(crate) fn excluded_candidates(&self) -> Vec<(Pos, Value)> {
+    /// The candidates this step removed
+    pub fn excluded_candidates(&self) -> Vec<(Pos, Value)> {
         match self {
             StrategyResult::NakedSingle(_, _) => Vec::new(),
             StrategyResult::HiddenSingle(_, _, _) => Vec::new(),
@@ -156,26 +560,361 @@ impl StrategyResult {
                 excluded_positions.iter().map(|pos| (*pos, *value)).collect(),
             StrategyResult::NakedSubset { excluded_candidates, .. } => excluded_candidates.clone(),
             StrategyResult::HiddenSubset { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::Fish { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::FinnedFish { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::ComplexFish { excluded_candidates, .. } => excluded_candidates.clone(),
             StrategyResult::XyWing { excluded_candidates, .. } => excluded_candidates.clone(),
             StrategyResult::XyzWing { excluded_candidates, .. } => excluded_candidates.clone(),
             StrategyResult::WxyzWing { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::Wing { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::WWing { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::RemotePair { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::UniqueRectangle { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::BivalueUniversalGrave { excluded_candidates, .. } => excluded_candidates.clone(),
             StrategyResult::SimpleColor { excluded_candidates, .. } => excluded_candidates.clone(),
             StrategyResult::MultiColor { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::XChain { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::XyChain { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::Aic { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::AlsXz { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::AlsXyWing { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::DeathBlossom { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::SingleDigitPattern { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::EmptyRectangle { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::SueDeCoq { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::JuniorExocet { excluded_candidates, .. } => excluded_candidates.clone(),
             StrategyResult::PatternOverlay { excluded_candidates, .. } => excluded_candidates.clone(),
+            StrategyResult::Trial { pos, value, .. } => vec![(*pos, *value)],
+            StrategyResult::ForcingChain { excluded_candidates, .. } => excluded_candidates.clone(),
+        }
+    }
+
+    /// The strategy which made this step, or `None` for a guess
+    pub fn strategy(&self) -> Option<Strategy> {
+        match self {
+            StrategyResult::NakedSingle(_, _) => Some(Strategy::NakedSingle),
+            StrategyResult::HiddenSingle(_, _, _) => Some(Strategy::HiddenSingle),
+            StrategyResult::GuessAndCheck(_, _) => None,
+            StrategyResult::LockedCandidate { .. } => Some(Strategy::LockedCandidate),
+            StrategyResult::NakedSubset { positions, .. } => match positions.len() {
+                2 => Some(Strategy::NakedPair),
+                3 => Some(Strategy::NakedTriple),
+                _ => Some(Strategy::NakedQuadruple),
+            },
+            StrategyResult::HiddenSubset { values, .. } => match values.len() {
+                2 => Some(Strategy::HiddenPair),
+                3 => Some(Strategy::HiddenTriple),
+                _ => Some(Strategy::HiddenQuadruple),
+            },
+            StrategyResult::Fish { base_lines, .. } => match base_lines.len() {
+                2 => Some(Strategy::XWing),
+                3 => Some(Strategy::Swordfish),
+                _ => Some(Strategy::Jellyfish),
+            },
+            StrategyResult::FinnedFish { base_lines, .. } => match base_lines.len() {
+                2 => Some(Strategy::FinnedXWing),
+                3 => Some(Strategy::FinnedSwordfish),
+                _ => Some(Strategy::FinnedJellyfish),
+            },
+            StrategyResult::ComplexFish { base_houses, mutant, .. } =>
+                Some(if *mutant { Strategy::MutantFish(base_houses.len()) } else { Strategy::FrankenFish(base_houses.len()) }),
+            StrategyResult::XyWing { .. } => Some(Strategy::XyWing),
+            StrategyResult::XyzWing { .. } => Some(Strategy::XyzWing),
+            StrategyResult::WxyzWing { .. } => Some(Strategy::WxyzWing),
+            StrategyResult::Wing { positions, .. } => Some(Strategy::Wing(positions.len())),
+            StrategyResult::WWing { .. } => Some(Strategy::WWing),
+            StrategyResult::RemotePair { .. } => Some(Strategy::RemotePair),
+            StrategyResult::UniqueRectangle { kind, .. } => match kind {
+                UniqueRectangleKind::Hidden => Some(Strategy::HiddenUniqueRectangle),
+                UniqueRectangleKind::Avoidable1 | UniqueRectangleKind::Avoidable2 => Some(Strategy::AvoidableRectangle),
+                _ => Some(Strategy::UniqueRectangle),
+            },
+            StrategyResult::BivalueUniversalGrave { .. } => Some(Strategy::BivalueUniversalGrave),
+            StrategyResult::SimpleColor { .. } => Some(Strategy::SimpleColor),
+            StrategyResult::MultiColor { color_positions, .. } => Some(Strategy::MultiColor(color_positions.len())),
+            StrategyResult::XChain { chain, .. } => Some(Strategy::XChain(chain.len())),
+            StrategyResult::XyChain { chain, .. } => Some(Strategy::XyChain(chain.len())),
+            StrategyResult::Aic { chain, .. } => Some(Strategy::Aic {
+                max_length: chain.len(),
+                grouped: chain.iter().any(|(positions, _, _)| positions.len() > 1),
+                als: chain.iter().any(|&(_, _, link_type)| link_type == LinkType::Als),
+            }),
+            StrategyResult::AlsXz { .. } => Some(Strategy::AlsXz),
+            StrategyResult::AlsXyWing { .. } => Some(Strategy::AlsXyWing),
+            StrategyResult::DeathBlossom { .. } => Some(Strategy::DeathBlossom),
+            StrategyResult::SingleDigitPattern { kind, .. } => match kind {
+                SingleDigitPatternKind::Skyscraper => Some(Strategy::Skyscraper),
+                SingleDigitPatternKind::TwoStringKite => Some(Strategy::TwoStringKite),
+                SingleDigitPatternKind::TurbotFish => Some(Strategy::TurbotFish),
+            },
+            StrategyResult::EmptyRectangle { .. } => Some(Strategy::EmptyRectangle),
+            StrategyResult::SueDeCoq { .. } => Some(Strategy::SueDeCoq),
+            StrategyResult::JuniorExocet { .. } => Some(Strategy::JuniorExocet),
+            StrategyResult::PatternOverlay { .. } => Some(Strategy::PatternOverlay),
+            StrategyResult::Trial { strategies, steps, .. } =>
+                Some(Strategy::Trial { max_steps: steps.len(), strategies }),
+            StrategyResult::ForcingChain { kind, .. } => Some(match kind {
+                ForcingChainKind::Cell => Strategy::CellForcingChain,
+                ForcingChainKind::Region(_) => Strategy::RegionForcingChain,
+                ForcingChainKind::Digit => Strategy::DigitForcingChain,
+            }),
         }
     }
 
-    pub(crate) fn required_candidates(&self) -> Vec<(Pos, Value)> {
+    /// The values this step placed
+    pub fn required_candidates(&self) -> Vec<(Pos, Value)> {
         match self {
             StrategyResult::NakedSingle(pos, val) => vec![(*pos, *val)],
             StrategyResult::HiddenSingle(pos, val, _) => vec![(*pos, *val)],
             StrategyResult::PatternOverlay { required_candidates, .. } => required_candidates.clone(),
+            StrategyResult::ForcingChain { required_candidates, .. } => required_candidates.clone(),
+            StrategyResult::BivalueUniversalGrave { required_candidates, .. } => required_candidates.clone(),
+            StrategyResult::Aic { required_candidates, .. } => required_candidates.clone(),
             StrategyResult::GuessAndCheck(_, _) => Vec::new(), // Handled separately when solving, not as a normal strategy
             _ => Vec::new()
         }
     }
 }
 
+fn positions_str(positions: impl IntoIterator<Item = Pos>) -> String {
+    positions.into_iter().map(|pos| pos.to_string()).collect::<Vec<_>>().join(",")
+}
+
+fn values_str(values: impl IntoIterator<Item = Value>) -> String {
+    values.into_iter().map(|val| val.to_string()).collect::<Vec<_>>().join("/")
+}
+
+/// The name of a wing with this many cells, e.g. `VWXYZ-Wing` for five
+fn wing_name(size: usize) -> String {
+    let letters = "RSTUVWXYZ";
+    format!("{}-Wing", &letters[letters.len() - size.min(letters.len()) ..])
+}
+
+/// The name of a fish with this many base houses
+fn fish_name(size: usize) -> &'static str {
+    ["X-Wing", "Swordfish", "Jellyfish", "Squirmbag", "Whale", "Leviathan"].get(size.wrapping_sub(2)).unwrap_or(&"Fish")
+}
+
+fn lines_str(lines: &[Line]) -> String {
+    houses_str(lines.iter().map(|&line| House::from_line(line)))
+}
+
+/// Formats houses compactly, e.g. `r27` for rows 2 and 7 or `c1b9` for column 1 and block 9
+fn houses_str(houses: impl IntoIterator<Item = House>) -> String {
+    let mut ret = String::new();
+    let mut prev_prefix = None;
+    for house in houses {
+        let (prefix, idx) = match (house.as_row(), house.as_col(), house.as_block()) {
+            (Some(row), _, _) => ('r', row.as_usize()),
+            (_, Some(col), _) => ('c', col.as_usize()),
+            (_, _, Some(block)) => ('b', block.as_usize()),
+            _ => unreachable!()
+        };
+        if prev_prefix != Some(prefix) {
+            ret.push(prefix);
+            prev_prefix = Some(prefix);
+        }
+        ret.push_str(&(idx + 1).to_string());
+    }
+    ret
+}
+
+/// Formats candidates grouped by value, e.g. `r1c1,r1c2<>5, r3c3<>7`
+fn candidates_str(candidates: &[(Pos, Value)], op: &str) -> Vec<String> {
+    Value::iter()
+        .filter_map(|val| {
+            let positions: Vec<Pos> = candidates.iter().filter(|&&(_, val2)| val == val2).map(|&(pos, _)| pos).collect();
+            if positions.is_empty() { return None }
+            Some(format!("{}{}{}", positions_str(positions), op, val))
+        })
+        .collect()
+}
+
+/// Formats the positions of a chain node, e.g. `r1c5`, or `r1c45` for a group in a line
+fn group_str(positions: &[Pos]) -> String {
+    let rows = || positions.iter().map(|pos| (pos.row() + 1).to_string()).collect::<String>();
+    let cols = || positions.iter().map(|pos| (pos.col() + 1).to_string()).collect::<String>();
+    if positions.iter().all(|pos| pos.row() == positions[0].row()) {
+        format!("r{}c{}", positions[0].row() + 1, cols())
+    } else if positions.iter().all(|pos| pos.col() == positions[0].col()) {
+        format!("r{}c{}", rows(), positions[0].col() + 1)
+    } else {
+        positions_str(positions.iter().cloned())
+    }
+}
+
+/// Formats a chain in Eureka notation, e.g. `(5)r1c1=r1c5-r3c4=r3c9` or `(1=2)r1c1-(2=1)r4c1`.
+/// Strongly linked nodes in the same cells are written together, and values are only given when
+/// they change.
+fn chain_str(chain: &[(Vec<Pos>, Value, LinkType)]) -> String {
+    let link_str = |link| if link == LinkType::Weak { "-" } else { "=" };
+    let mut ret = String::new();
+    let mut prev_val = None;
+    let mut idx = 0;
+    while idx < chain.len() {
+        let (positions, val, link) = &chain[idx];
+        if idx > 0 {
+            ret.push_str(link_str(chain[idx - 1].2));
+        }
+        match chain.get(idx + 1) {
+            Some((positions2, val2, _)) if positions2 == positions && *link != LinkType::Weak => {
+                ret.push_str(&format!("({}={}){}", val, val2, group_str(positions)));
+                prev_val = Some(*val2);
+                idx += 2;
+            },
+            _ => {
+                if prev_val != Some(*val) {
+                    ret.push_str(&format!("({})", val));
+                }
+                ret.push_str(&group_str(positions));
+                prev_val = Some(*val);
+                idx += 1;
+            }
+        }
+    }
+    ret
+}
+
+/// Formats a chain of single candidates, as for `chain_str`
+fn single_chain_str(chain: &[(Pos, Value, LinkType)]) -> String {
+    chain_str(&chain.iter().map(|&(pos, val, link)| (vec![pos], val, link)).collect::<Vec<_>>())
+}
+
+/// Renders the step in the standard notation, e.g. `Hidden Single: r4c5=7 in block 5` or
+/// `XY-Wing 1/2/3 at r1c1,r1c5,r3c2 => r3c5<>3`
+impl fmt::Display for StrategyResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StrategyResult::NakedSingle(pos, val) => return write!(f, "Naked Single: {}={}", pos, val),
+            StrategyResult::HiddenSingle(pos, val, house) =>
+                return write!(f, "Hidden Single: {}={} in {}", pos, val, house),
+            StrategyResult::GuessAndCheck(pos, val) => return write!(f, "Guess: {}={}", pos, val),
+            StrategyResult::LockedCandidate { value, positions, block, line, pointing, .. } => {
+                let kind = if *pointing { "Pointing" } else { "Claiming" };
+                let house = if *pointing { block.to_string() } else { line.to_string() };
+                write!(f, "Locked Candidate ({}): {} at {} in {}", kind, value, positions_str(positions.iter().cloned()), house)?;
+            },
+            StrategyResult::NakedSubset { positions, values, .. } => {
+                let name = ["Pair", "Triple", "Quadruple"].get(positions.len().wrapping_sub(2)).unwrap_or(&"Subset");
+                write!(f, "Naked {}: {} at {}", name, values_str(values.iter().cloned()), positions_str(positions.iter().cloned()))?;
+            },
+            StrategyResult::HiddenSubset { positions, values, house, .. } => {
+                let name = ["Pair", "Triple", "Quadruple"].get(values.len().wrapping_sub(2)).unwrap_or(&"Subset");
+                write!(f, "Hidden {}: {} at {} in {}", name, values_str(values.iter().cloned()), positions_str(positions.iter().cloned()), house)?;
+            },
+            StrategyResult::Fish { value, base_lines, cover_lines, .. } =>
+                write!(f, "{}: {} {} {}", fish_name(base_lines.len()), value, lines_str(base_lines), lines_str(cover_lines))?,
+            StrategyResult::FinnedFish { value, base_lines, cover_lines, fins, sashimi, .. } => {
+                let kind = if *sashimi { "Sashimi" } else { "Finned" };
+                let fins: Vec<_> = fins.iter().map(|pos| format!("f{}", pos)).collect();
+                write!(f, "{} {}: {} {} {} {}", kind, fish_name(base_lines.len()), value, lines_str(base_lines),
+                    lines_str(cover_lines), fins.join(" "))?;
+            },
+            StrategyResult::ComplexFish { value, base_houses, cover_houses, mutant, .. } => {
+                let kind = if *mutant { "Mutant" } else { "Franken" };
+                write!(f, "{} {}: {} {} {}", kind, fish_name(base_houses.len()), value, houses_str(base_houses.iter().cloned()),
+                    houses_str(cover_houses.iter().cloned()))?;
+            },
+            StrategyResult::XyWing { positions, values, .. } =>
+                write!(f, "XY-Wing {} at {}", values_str(values.iter().cloned()), positions_str(positions.iter().cloned()))?,
+            StrategyResult::XyzWing { positions, values, .. } =>
+                write!(f, "XYZ-Wing {} at {}", values_str(values.iter().cloned()), positions_str(positions.iter().cloned()))?,
+            StrategyResult::WxyzWing { positions, values, .. } =>
+                write!(f, "WXYZ-Wing {} at {}", values_str(values.iter().cloned()), positions_str(positions.iter().cloned()))?,
+            StrategyResult::Wing { positions, values, .. } =>
+                write!(f, "{} {} at {}", wing_name(positions.len()), values_str(values.iter().cloned()),
+                    positions_str(positions.iter().cloned()))?,
+            StrategyResult::WWing { positions, values, link, .. } =>
+                write!(f, "W-Wing {} at {} connected by {}={}", values_str(values.iter().cloned()),
+                    positions_str(positions.iter().cloned()), link[0], link[1])?,
+            StrategyResult::RemotePair { values, chain, .. } =>
+                write!(f, "Remote Pair {} at {}", values_str(values.iter().cloned()), positions_str(chain.iter().cloned()))?,
+            StrategyResult::UniqueRectangle { positions, values, kind, .. } => {
+                let name = match kind {
+                    UniqueRectangleKind::Type1 => "Unique Rectangle Type 1",
+                    UniqueRectangleKind::Type2 => "Unique Rectangle Type 2",
+                    UniqueRectangleKind::Type3 => "Unique Rectangle Type 3",
+                    UniqueRectangleKind::Type4 => "Unique Rectangle Type 4",
+                    UniqueRectangleKind::Type5 => "Unique Rectangle Type 5",
+                    UniqueRectangleKind::Type6 => "Unique Rectangle Type 6",
+                    UniqueRectangleKind::Hidden => "Hidden Unique Rectangle",
+                    UniqueRectangleKind::Avoidable1 => "Avoidable Rectangle Type 1",
+                    UniqueRectangleKind::Avoidable2 => "Avoidable Rectangle Type 2",
+                };
+                write!(f, "{}: {} at {}", name, values_str(values.iter().cloned()), positions_str(positions.iter().cloned()))?;
+            },
+            StrategyResult::BivalueUniversalGrave { extra_candidates, .. } =>
+                write!(f, "Bivalue Universal Grave + {}", extra_candidates.len())?,
+            StrategyResult::SimpleColor { value, color_positions, color_wrap, .. } => {
+                let kind = if *color_wrap { "Wrap" } else { "Trap" };
+                write!(f, "Simple Colors {}: {} ({} / {})", kind, value,
+                    positions_str(color_positions[0].iter().cloned()), positions_str(color_positions[1].iter().cloned()))?;
+            },
+            StrategyResult::MultiColor { value, color_positions, .. } => {
+                let colors: Vec<_> = color_positions.iter()
+                    .map(|[a, b]| format!("({} / {})", positions_str(a.iter().cloned()), positions_str(b.iter().cloned())))
+                    .collect();
+                write!(f, "Multi Colors: {} {}", value, colors.join(", "))?;
+            },
+            StrategyResult::XChain { chain, .. } => write!(f, "X-Chain: {}", single_chain_str(chain))?,
+            StrategyResult::XyChain { chain, .. } => write!(f, "XY-Chain: {}", single_chain_str(chain))?,
+            StrategyResult::Aic { chain, kind, .. } => match kind {
+                AicKind::Chain => write!(f, "AIC: {}", chain_str(chain))?,
+                AicKind::DiscontinuousLoop => write!(f, "Discontinuous Nice Loop: {}", chain_str(chain))?,
+                // Close the loop by repeating the first candidate
+                AicKind::ContinuousLoop => write!(f, "Continuous Nice Loop: {}",
+                    chain_str(&[&chain[..], &chain[..1]].concat()))?,
+            },
+            StrategyResult::AlsXz { sets, restricted_commons, .. } => {
+                let kind = if restricted_commons.len() > 1 { "Doubly Linked ALS-XZ" } else { "ALS-XZ" };
+                write!(f, "{}: A={} B={} X={}", kind, positions_str(sets[0].iter().cloned()), positions_str(sets[1].iter().cloned()),
+                    values_str(restricted_commons.iter().cloned()))?;
+            },
+            StrategyResult::AlsXyWing { sets, restricted_commons, .. } =>
+                write!(f, "ALS-XY-Wing: A={} B={} C={} X={} Y={}", positions_str(sets[0].iter().cloned()),
+                    positions_str(sets[1].iter().cloned()), positions_str(sets[2].iter().cloned()), restricted_commons[0],
+                    restricted_commons[1])?,
+            StrategyResult::DeathBlossom { stem, petals, .. } => {
+                let petals: Vec<_> = petals.iter()
+                    .map(|(val, positions)| format!("({}) {}", val, positions_str(positions.iter().cloned())))
+                    .collect();
+                write!(f, "Death Blossom: {} {}", stem, petals.join(" "))?;
+            },
+            StrategyResult::SingleDigitPattern { value, positions, kind, .. } => {
+                let name = match kind {
+                    SingleDigitPatternKind::Skyscraper => "Skyscraper",
+                    SingleDigitPatternKind::TwoStringKite => "2-String Kite",
+                    SingleDigitPatternKind::TurbotFish => "Turbot Fish",
+                };
+                write!(f, "{}: {} in {},{} (connected by {},{})", name, value, positions[0], positions[3], positions[1],
+                    positions[2])?;
+            },
+            StrategyResult::EmptyRectangle { value, block, link, .. } =>
+                write!(f, "Empty Rectangle: {} in {} ({}={})", value, block, link[0], link[1])?,
+            StrategyResult::SueDeCoq { block, line, positions, line_positions, block_positions, .. } =>
+                write!(f, "Sue de Coq: {} with {} in {} and {} in {}", positions_str(positions.iter().cloned()),
+                    positions_str(line_positions.iter().cloned()), line, positions_str(block_positions.iter().cloned()), block)?,
+            StrategyResult::JuniorExocet { base, targets, values, cross_lines, .. } =>
+                write!(f, "Junior Exocet: {} at {},{} with targets {},{} and cross-lines {}", values_str(values.iter().cloned()),
+                    base[0], base[1], targets[0], targets[1], lines_str(cross_lines))?,
+            StrategyResult::PatternOverlay { value, remaining_patterns, .. } =>
+                write!(f, "Pattern Overlay: {} with {} remaining patterns", value, remaining_patterns)?,
+            StrategyResult::Trial { pos, value, steps, contradiction, .. } =>
+                write!(f, "Trial: {}={} leads to a contradiction in {} steps ({})", pos, value, steps.len(), contradiction)?,
+            StrategyResult::ForcingChain { kind, branches, .. } => match kind {
+                ForcingChainKind::Cell => write!(f, "Cell Forcing Chain: {}={}", branches[0].pos,
+                    values_str(branches.iter().map(|branch| branch.value)))?,
+                ForcingChainKind::Region(house) => write!(f, "Region Forcing Chain: {} in {} at {}", branches[0].value, house,
+                    positions_str(branches.iter().map(|branch| branch.pos)))?,
+                ForcingChainKind::Digit => write!(f, "Digit Forcing Chain: {} at {}", branches[0].value, branches[0].pos)?,
+            },
+        }
+        let mut conclusions = candidates_str(&self.required_candidates(), "=");
+        conclusions.extend(candidates_str(&self.excluded_candidates(), "<>"));
+        write!(f, " => {}", conclusions.join(", "))
+    }
+}
+
 /// A naked subset in some house also means there can't be a higher-order naked subset with any of
 /// the same positions in that house, or a hidden subset with any of the locked values (and vice
 /// versa). By keeping track of these known subsets, we skip searching those locations on
@@ -194,3 +933,121 @@ impl Default for KnownSubsets {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!("NakedPair".parse(), Ok(Strategy::NakedPair));
+        assert_eq!(" xywing ".parse(), Ok(Strategy::XyWing));
+        assert_eq!("MultiColor".parse(), Ok(Strategy::MultiColor(usize::MAX)));
+        assert_eq!("MultiColor(3)".parse(), Ok(Strategy::MultiColor(3)));
+        assert_eq!("FrankenFish".parse(), Ok(Strategy::FrankenFish(4)));
+        assert_eq!("mutantfish(3)".parse(), Ok(Strategy::MutantFish(3)));
+        assert_eq!("BUG".parse(), Ok(Strategy::BivalueUniversalGrave));
+        assert_eq!("XyChain".parse(), Ok(Strategy::XyChain(usize::MAX)));
+        assert_eq!("xchain(8)".parse(), Ok(Strategy::XChain(8)));
+        assert_eq!("AIC(12)".parse(), Ok(Strategy::Aic { max_length: 12, grouped: false, als: false }));
+        assert_eq!("GroupedAic".parse(), Ok(Strategy::Aic { max_length: usize::MAX, grouped: true, als: false }));
+        assert_eq!("AlsAic(10)".parse(), Ok(Strategy::Aic { max_length: 10, grouped: true, als: true }));
+        assert_eq!("ALSXZ".parse(), Ok(Strategy::AlsXz));
+        assert_eq!("DeathBlossom".parse(), Ok(Strategy::DeathBlossom));
+        assert_eq!("2StringKite".parse(), Ok(Strategy::TwoStringKite));
+        assert_eq!("SueDeCoq".parse(), Ok(Strategy::SueDeCoq));
+        assert_eq!("JE".parse(), Ok(Strategy::JuniorExocet));
+        assert_eq!("HouseForcingChain".parse(), Ok(Strategy::RegionForcingChain));
+        assert_eq!("RemotePairs".parse(), Ok(Strategy::RemotePair));
+        assert_eq!("WWing".parse(), Ok(Strategy::WWing));
+        assert_eq!("Trial(5)".parse(), Ok(Strategy::Trial { max_steps: 5, strategies: TRIAL }));
+        assert_eq!("Wing".parse(), Ok(Strategy::Wing(9)));
+        assert_eq!("VWXYZWing".parse(), Ok(Strategy::Wing(5)));
+        assert!(matches!("MultiColor(x)".parse::<Strategy>(), Err(StrategyParseError::InvalidParameter(_))));
+        assert!(matches!("NakedPair(2)".parse::<Strategy>(), Err(StrategyParseError::InvalidParameter(_))));
+        assert!(matches!("NakedFoo".parse::<Strategy>(), Err(StrategyParseError::UnknownStrategy(_))));
+    }
+
+    #[test]
+    fn test_parse_strategy_list() {
+        assert_eq!(Strategy::parse_list("ALL"), Ok(ALL.to_vec()));
+        assert_eq!(Strategy::parse_list("FAST"), Ok(FAST.to_vec()));
+        assert_eq!(Strategy::parse_list("NakedSingle, hiddensingle,MultiColor(3)"),
+            Ok(vec![Strategy::NakedSingle, Strategy::HiddenSingle, Strategy::MultiColor(3)]));
+        assert_eq!(Strategy::parse_list("NakedSingle,NakedFoo"), Err(StrategyParseError::UnknownStrategy("NakedFoo".to_string())));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(StrategyResult::NakedSingle(Pos::new(38), Value::new(1)).to_string(),
+            "Naked Single: r5c3=2");
+        assert_eq!(StrategyResult::HiddenSingle(Pos::new(39), Value::new(6), House::new(22)).to_string(),
+            "Hidden Single: r5c4=7 in block 5");
+        assert_eq!(StrategyResult::LockedCandidate {
+                value: Value::new(6), excluded_positions: vec![Pos::new(47)],
+                positions: vec![Pos::new(11), Pos::new(20)], block: Block::new(0), line: Line::new(11),
+                pointing: true
+            }.to_string(),
+            "Locked Candidate (Pointing): 7 at r2c3,r3c3 in block 1 => r6c3<>7");
+        assert_eq!(StrategyResult::NakedSubset {
+                excluded_candidates: vec![
+                    (Pos::new(54), Value::new(1)), (Pos::new(58), Value::new(5)),
+                    (Pos::new(80), Value::new(1)), (Pos::new(80), Value::new(5)),
+                ],
+                positions: vec![Pos::new(61), Pos::new(62)],
+                values: vec![Value::new(1), Value::new(5)]
+            }.to_string(),
+            "Naked Pair: 2/6 at r7c8,r7c9 => r7c1,r9c9<>2, r7c5,r9c9<>6");
+        assert_eq!(StrategyResult::XyWing {
+                excluded_candidates: vec![(Pos::new(22), Value::new(2))],
+                positions: [Pos::new(0), Pos::new(4), Pos::new(19)],
+                values: [Value::new(0), Value::new(1), Value::new(2)]
+            }.to_string(),
+            "XY-Wing 1/2/3 at r1c1,r1c5,r3c2 => r3c5<>3");
+        assert_eq!(StrategyResult::Fish {
+                excluded_candidates: vec![(Pos::new(38), Value::new(4))],
+                value: Value::new(4),
+                base_lines: vec![Line::new(1), Line::new(6)],
+                cover_lines: vec![Line::new(11), Line::new(16)]
+            }.to_string(),
+            "X-Wing: 5 r27 c38 => r5c3<>5");
+        assert_eq!(StrategyResult::FinnedFish {
+                excluded_candidates: vec![(Pos::new(13), Value::new(4))],
+                value: Value::new(4),
+                base_lines: vec![Line::new(14), Line::new(15)],
+                cover_lines: vec![Line::new(1), Line::new(6)],
+                fins: vec![Pos::new(5)],
+                sashimi: true
+            }.to_string(),
+            "Sashimi X-Wing: 5 c67 r27 fr1c6 => r2c5<>5");
+        assert_eq!(StrategyResult::XChain {
+                excluded_candidates: vec![(Pos::new(19), Value::new(5))],
+                chain: vec![
+                    (Pos::new(26), Value::new(5), LinkType::Strong), (Pos::new(71), Value::new(5), LinkType::Weak),
+                    (Pos::new(61), Value::new(5), LinkType::Strong), (Pos::new(54), Value::new(5), LinkType::Weak),
+                ]
+            }.to_string(),
+            "X-Chain: (6)r3c9=r8c9-r7c8=r7c1 => r3c2<>6");
+        assert_eq!(StrategyResult::XyChain {
+                excluded_candidates: vec![(Pos::new(5), Value::new(6))],
+                chain: vec![
+                    (Pos::new(2), Value::new(6), LinkType::Strong), (Pos::new(2), Value::new(2), LinkType::Weak),
+                    (Pos::new(74), Value::new(2), LinkType::Strong), (Pos::new(74), Value::new(3), LinkType::Weak),
+                    (Pos::new(78), Value::new(3), LinkType::Strong), (Pos::new(78), Value::new(1), LinkType::Weak),
+                    (Pos::new(77), Value::new(1), LinkType::Strong), (Pos::new(77), Value::new(6), LinkType::Weak),
+                ]
+            }.to_string(),
+            "XY-Chain: (7=3)r1c3-(3=4)r9c3-(4=2)r9c7-(2=7)r9c6 => r1c6<>7");
+        assert_eq!(StrategyResult::Aic {
+                excluded_candidates: vec![(Pos::new(31), Value::new(0)), (Pos::new(39), Value::new(0))],
+                required_candidates: vec![],
+                chain: vec![
+                    (vec![Pos::new(40)], Value::new(0), LinkType::Strong), (vec![Pos::new(40)], Value::new(8), LinkType::Weak),
+                    (vec![Pos::new(43)], Value::new(8), LinkType::Strong), (vec![Pos::new(52)], Value::new(8), LinkType::Weak),
+                    (vec![Pos::new(52)], Value::new(0), LinkType::Strong), (vec![Pos::new(48)], Value::new(0), LinkType::Weak),
+                ],
+                kind: AicKind::ContinuousLoop
+            }.to_string(),
+            "Continuous Nice Loop: (1=9)r5c5-r5c8=r6c8-(1)r6c8=r6c4-r5c5 => r4c5,r5c4<>1");
+    }
+}
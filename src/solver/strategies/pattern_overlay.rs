@@ -11,7 +11,10 @@ fn visit_remaining_patterns(mut row_iter: impl Iterator<Item = Row> + Clone,
 {
     while let Some(row) = row_iter.next() {
         let row_remaining = remaining & row.members_bitset();
-        if row_remaining.is_empty() { continue }
+        if row_remaining.is_empty() {
+            if (known & row.members_bitset()).is_empty() { return true } // Dead end, no valid pattern
+            continue
+        }
         for pos in row_remaining.iter() {
             let mut known2 = known;
             let mut remaining2 = remaining;
@@ -34,8 +37,8 @@ fn visit_remaining_patterns(mut row_iter: impl Iterator<Item = Row> + Clone,
     *all_accum &= known;
     *any_accum |= known;
 
-    // Keep going if we could make progress
-    !all_accum.is_empty() || any_accum != initial_candidates
+    // Keep going if we could make progress (knowns are in every pattern, so ignore them)
+    !all_accum.is_empty() || (*any_accum & *initial_candidates) != *initial_candidates
 }
 
 pub(crate) fn pattern_overlay_for_value(sudoku: &Sudoku, val: Value) -> Option<StrategyResult> {
@@ -46,18 +49,22 @@ pub(crate) fn pattern_overlay_for_value(sudoku: &Sudoku, val: Value) -> Option<S
     let mut any_accum = PosBitSet::NONE;
     let mut cnt = 0;
 
-    if visit_remaining_patterns(Row::iter(), &candidates, candidates, knowns,
+    if !visit_remaining_patterns(Row::iter(), &candidates, candidates, knowns,
         &mut all_accum, &mut any_accum, &mut cnt)
     {
-        Some(StrategyResult::PatternOverlay {
-            excluded_candidates: candidates.difference(any_accum).iter().map(|pos| (pos, val)).collect(),
-            required_candidates: all_accum.iter().map(|pos| (pos, val)).collect(),
-            value: val,
-            remaining_patterns: cnt,
-        })
-    } else {
-        None
+        return None
     }
+
+    let excluded_candidates: Vec<_> = candidates.difference(any_accum).iter().map(|pos| (pos, val)).collect();
+    let required_candidates: Vec<_> = all_accum.iter().map(|pos| (pos, val)).collect();
+    // The search can finish without stopping early even if nothing was found
+    if excluded_candidates.is_empty() && required_candidates.is_empty() { return None }
+    Some(StrategyResult::PatternOverlay {
+        excluded_candidates,
+        required_candidates,
+        value: val,
+        remaining_patterns: cnt,
+    })
 }
 
 pub(crate) fn pattern_overlay(sudoku: &Sudoku) -> Option<StrategyResult> {
@@ -66,3 +73,15 @@ pub(crate) fn pattern_overlay(sudoku: &Sudoku) -> Option<StrategyResult> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_progress() {
+        // Every candidate is part of some pattern, so there is nothing to exclude or require
+        let sudoku = Sudoku::from_line("1.......2.9.4...5...6...7...5.9.3.......7.......85..4.7.....6...3...9.8...2.....1").unwrap();
+        assert_eq!(pattern_overlay(&sudoku), None);
+    }
+}
@@ -0,0 +1,263 @@
+use super::{coloring::conjugate_pairs, StrategyResult};
+use crate::{
+    solver::{Block, Col, House, Line, PosBitSet, Row},
+    Pos, Sudoku, Value,
+};
+
+/// Which of the named two strong link patterns was found
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SingleDigitPatternKind {
+    /// Strong links in two parallel lines, with one end of each in the same perpendicular line
+    Skyscraper,
+    /// A strong link in a row and one in a column, with one end of each in the same block
+    TwoStringKite,
+    /// Any other pair of strong links with one end of each seeing each other, such as one in a
+    /// block
+    TurbotFish,
+}
+
+/// The kind of pattern made by the strong links `pos1=pos2` and `pos3=pos4`, where `pos2` sees
+/// `pos3`
+fn pattern_kind(pos1: Pos, pos2: Pos, pos3: Pos, pos4: Pos) -> SingleDigitPatternKind {
+    let same_row = |pos1: Pos, pos2: Pos| pos1.row() == pos2.row();
+    let same_col = |pos1: Pos, pos2: Pos| pos1.col() == pos2.col();
+    let same_block = |pos1: Pos, pos2: Pos| pos1.block() == pos2.block();
+
+    if (same_row(pos1, pos2) && same_row(pos3, pos4) && same_col(pos2, pos3) && !same_col(pos1, pos4)) ||
+        (same_col(pos1, pos2) && same_col(pos3, pos4) && same_row(pos2, pos3) && !same_row(pos1, pos4))
+    {
+        SingleDigitPatternKind::Skyscraper
+    } else if ((same_row(pos1, pos2) && same_col(pos3, pos4)) || (same_col(pos1, pos2) && same_row(pos3, pos4))) &&
+        same_block(pos2, pos3) && !same_block(pos1, pos2) && !same_block(pos3, pos4)
+    {
+        SingleDigitPatternKind::TwoStringKite
+    } else {
+        SingleDigitPatternKind::TurbotFish
+    }
+}
+
+pub(crate) fn skyscraper(sudoku: &Sudoku) -> Option<StrategyResult> {
+    two_strong_links(sudoku, SingleDigitPatternKind::Skyscraper)
+}
+
+pub(crate) fn two_string_kite(sudoku: &Sudoku) -> Option<StrategyResult> {
+    two_strong_links(sudoku, SingleDigitPatternKind::TwoStringKite)
+}
+
+pub(crate) fn turbot_fish(sudoku: &Sudoku) -> Option<StrategyResult> {
+    two_strong_links(sudoku, SingleDigitPatternKind::TurbotFish)
+}
+
+/// Two strong links `A=B` and `C=D` of a value, where `B` sees `C`. One of `A` or `D` must have the
+/// value, so it's excluded from anywhere seeing both.
+fn two_strong_links(sudoku: &Sudoku, kind: SingleDigitPatternKind) -> Option<StrategyResult> {
+    for val in Value::iter() {
+        let candidates = sudoku.get_candidates_by_value(val);
+        // Pairs in the intersection of a block and a line are found in both houses
+        let mut links: Vec<(Pos, Pos)> = conjugate_pairs(candidates).collect();
+        links.sort_unstable();
+        links.dedup();
+
+        for (idx, &(link1_pos1, link1_pos2)) in links.iter().enumerate() {
+            for &(link2_pos1, link2_pos2) in &links[idx + 1 ..] {
+                for &(pos1, pos2) in &[(link1_pos1, link1_pos2), (link1_pos2, link1_pos1)] {
+                    for &(pos3, pos4) in &[(link2_pos1, link2_pos2), (link2_pos2, link2_pos1)] {
+                        if pos3 == pos1 || pos3 == pos2 || pos4 == pos1 || pos4 == pos2 { continue }
+                        if !pos2.neighbors_bitset().contains(pos3) { continue }
+                        if pattern_kind(pos1, pos2, pos3, pos4) != kind { continue }
+
+                        let excluded_positions = pos1.neighbors_bitset() & pos4.neighbors_bitset() & candidates;
+                        if !excluded_positions.is_empty() {
+                            return Some(StrategyResult::SingleDigitPattern {
+                                excluded_candidates: excluded_positions.iter().map(|pos| (pos, val)).collect(),
+                                value: val,
+                                positions: [pos1, pos2, pos3, pos4],
+                                kind,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The cells of the position's row
+fn row_bitset(pos: Pos) -> PosBitSet {
+    House::from_row(Row::new(pos.row() as usize)).members_bitset()
+}
+
+/// The cells of the position's column
+fn col_bitset(pos: Pos) -> PosBitSet {
+    House::from_col(Col::new(pos.col() as usize)).members_bitset()
+}
+
+/// A block where a value is confined to one row and one column, and a strong link in a line
+/// crossing that row (or column) outside the block. If the end of the link in the row is false,
+/// the other end is true, and otherwise the block's value is in the column, so the cell where the
+/// column meets the line of the other end is excluded.
+pub(crate) fn empty_rectangle(sudoku: &Sudoku) -> Option<StrategyResult> {
+    for val in Value::iter() {
+        let candidates = sudoku.get_candidates_by_value(val);
+        let links: Vec<(Pos, Pos)> = conjugate_pairs(candidates)
+            .filter(|&(pos1, pos2)| pos1.block() != pos2.block())
+            .flat_map(|(pos1, pos2)| vec![(pos1, pos2), (pos2, pos1)])
+            .collect();
+
+        for block in Block::iter() {
+            let block_candidates = candidates & block.members_bitset();
+            if block_candidates.len() < 2 { continue }
+
+            let lines: Vec<Line> = block.intersecting_lines_iter().collect();
+            for row in lines.iter().filter(|line| line.as_row().is_some()) {
+                for col in lines.iter().filter(|line| line.as_col().is_some()) {
+                    let row_candidates = block_candidates & row.members_bitset();
+                    let col_candidates = block_candidates & col.members_bitset();
+                    if (row_candidates | col_candidates) != block_candidates { continue }
+                    // Otherwise it's just a locked candidate
+                    if row_candidates.difference(col_candidates).is_empty() ||
+                        col_candidates.difference(row_candidates).is_empty()
+                    {
+                        continue
+                    }
+
+                    for &(pos1, pos2) in &links {
+                        if block.members_bitset().contains(pos1) { continue }
+                        let excluded_positions = if pos1.col() == pos2.col() && row.members_bitset().contains(pos1) {
+                            row_bitset(pos2) & col.members_bitset()
+                        } else if pos1.row() == pos2.row() && col.members_bitset().contains(pos1) {
+                            row.members_bitset() & col_bitset(pos2)
+                        } else {
+                            continue
+                        };
+                        let excluded_positions = (excluded_positions & candidates).difference(block.members_bitset());
+                        if !excluded_positions.is_empty() {
+                            return Some(StrategyResult::EmptyRectangle {
+                                excluded_candidates: excluded_positions.iter().map(|pos| (pos, val)).collect(),
+                                value: val,
+                                block,
+                                positions: block_candidates.iter().collect(),
+                                link: [pos1, pos2],
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SKYSCRAPER_EXAMPLE1: &str = "\
+4    39   37  | 8     6     19    | 127  27   5
+57   8    1   | 2457  257   2457  | 6    9    3
+579  6    2   | 57    19    3     | 17   8    4
+--------------+-------------------+-------------
+379  249  457 | 1     579   6     | 8    347  29
+8    249  6   | 3457  3579  4579  | 457  1    29
+379  1    457 | 2457  8     24579 | 457  347  6
+--------------+-------------------+-------------
+6    34   8   | 235   1235  125   | 9    24   7
+2    7    9   | 6     4     8     | 3    5    1
+1    5    34  | 9     237   27    | 24   6    8";
+
+    const TWO_STRING_KITE_EXAMPLE1: &str = "\
+8  9    2  | 3    4     1    | 56    56  7
+5  7    3  | 9    68    26   | 28    4   1
+1  6    4  | 28   57    57   | 3     28  9
+-----------+-----------------+---------------
+7  238  9  | 268  358   4    | 258   1   2568
+6  238  15 | 12   3589  2359 | 4     7   258
+4  28   15 | 7    1568  256  | 2568  9   3
+-----------+-----------------+---------------
+3  5    6  | 4    79    79   | 1     28  28
+9  1    8  | 5    2     36   | 7     36  4
+2  4    7  | 16   13    8    | 9     35  56";
+
+    const TURBOT_FISH_EXAMPLE1: &str = "\
+1567   4     3     | 2     1567  9   | 178    678  168
+2      679   1679  | 1367  1367  8   | 4      679  5
+8      5679  15679 | 167   1567  4   | 179    3    2
+-------------------+-----------------+----------------
+67     2     678   | 5     4     37  | 38     1    9
+179    3789  4     | 1379  1378  2   | 6      5    38
+159    3589  1589  | 1369  1368  13  | 2      4    7
+-------------------+-----------------+----------------
+457    578   2     | 1347  9     6   | 13578  78   138
+45679  1     56789 | 347   37    357 | 35789  2    368
+3      5679  5679  | 8     2     157 | 1579   679  4";
+
+    const EMPTY_RECTANGLE_EXAMPLE1: &str = "\
+8  9    2  | 3    4     1    | 56    56  7
+5  7    3  | 9    68    26   | 28    4   1
+1  6    4  | 28   57    57   | 3     28  9
+-----------+-----------------+--------------
+7  238  9  | 268  358   4    | 25    1   568
+6  238  15 | 12   3589  2359 | 4     7   258
+4  28   15 | 7    1568  256  | 2568  9   3
+-----------+-----------------+--------------
+3  5    6  | 4    79    79   | 1     28  28
+9  1    8  | 5    2     36   | 7     36  4
+2  4    7  | 16   13    8    | 9     35  56";
+
+    #[test]
+    fn test_skyscraper_example1() {
+        let sudoku = Sudoku::from_pencilmarks(SKYSCRAPER_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::SingleDigitPattern {
+            excluded_candidates: vec![
+                (Pos::new(18), Value::new(8)), (Pos::new(28), Value::new(8)), (Pos::new(37), Value::new(8)),
+            ],
+            value: Value::new(8),
+            positions: [Pos::new(1), Pos::new(5), Pos::new(50), Pos::new(45)],
+            kind: SingleDigitPatternKind::Skyscraper,
+        };
+        assert_eq!(step_res.to_string(), "Skyscraper: 9 in r1c2,r6c1 (connected by r1c6,r6c6) => r3c1,r4c2,r5c2<>9");
+        assert_eq!(skyscraper(&sudoku), Some(step_res));
+    }
+
+    #[test]
+    fn test_two_string_kite_example1() {
+        let sudoku = Sudoku::from_pencilmarks(TWO_STRING_KITE_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::SingleDigitPattern {
+            excluded_candidates: vec![(Pos::new(33), Value::new(7))],
+            value: Value::new(7),
+            positions: [Pos::new(15), Pos::new(13), Pos::new(21), Pos::new(30)],
+            kind: SingleDigitPatternKind::TwoStringKite,
+        };
+        assert_eq!(step_res.to_string(), "2-String Kite: 8 in r2c7,r4c4 (connected by r2c5,r3c4) => r4c7<>8");
+        assert_eq!(skyscraper(&sudoku), None);
+        assert_eq!(two_string_kite(&sudoku), Some(step_res));
+    }
+
+    #[test]
+    fn test_turbot_fish_example1() {
+        let sudoku = Sudoku::from_pencilmarks(TURBOT_FISH_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::SingleDigitPattern {
+            excluded_candidates: vec![(Pos::new(60), Value::new(7))],
+            value: Value::new(7),
+            positions: [Pos::new(33), Pos::new(29), Pos::new(65), Pos::new(55)],
+            kind: SingleDigitPatternKind::TurbotFish,
+        };
+        assert_eq!(step_res.to_string(), "Turbot Fish: 8 in r4c7,r7c2 (connected by r4c3,r8c3) => r7c7<>8");
+        assert_eq!(turbot_fish(&sudoku), Some(step_res));
+    }
+
+    #[test]
+    fn test_empty_rectangle_example1() {
+        let sudoku = Sudoku::from_pencilmarks(EMPTY_RECTANGLE_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::EmptyRectangle {
+            excluded_candidates: vec![(Pos::new(41), Value::new(1))],
+            value: Value::new(1),
+            block: Block::new(5),
+            positions: vec![Pos::new(33), Pos::new(44), Pos::new(51)],
+            link: [Pos::new(15), Pos::new(14)],
+        };
+        assert_eq!(step_res.to_string(), "Empty Rectangle: 2 in block 6 (r2c7=r2c6) => r5c6<>2");
+        assert_eq!(empty_rectangle(&sudoku), Some(step_res));
+    }
+}
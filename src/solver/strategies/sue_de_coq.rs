@@ -0,0 +1,114 @@
+use super::StrategyResult;
+use crate::{
+    solver::{Block, PosBitSet, ValueBitSet},
+    Pos, Sudoku,
+};
+
+/// Every non-empty subset of the positions, with the candidates between them
+fn subsets(sudoku: &Sudoku, positions: PosBitSet) -> Vec<(PosBitSet, ValueBitSet)> {
+    let positions: Vec<Pos> = positions.iter().collect();
+    (1 .. 1usize << positions.len())
+        .map(|mask| {
+            let subset: PosBitSet = positions.iter().enumerate()
+                .filter(|&(idx, _)| mask & (1 << idx) != 0)
+                .map(|(_, &pos)| pos)
+                .collect();
+            let values = subset.iter().fold(ValueBitSet::NONE, |values, pos| values | sudoku.get_candidates_by_pos(pos));
+            (subset, values)
+        })
+        .collect()
+}
+
+/// Two or three cells in the intersection of a block and a line, with at least two more
+/// candidates than cells, and cells in the rest of the line and the rest of the block with no
+/// candidates in common. If there are as many candidates as cells in total, each value must appear
+/// once: those of the line cells in the line, those of the block cells in the block, and the rest
+/// in the intersection.
+pub(crate) fn sue_de_coq(sudoku: &Sudoku) -> Option<StrategyResult> {
+    let unsolved: PosBitSet = Pos::iter().filter(|&pos| !sudoku.get_candidates_by_pos(pos).is_empty()).collect();
+    for block in Block::iter() {
+        let block_positions = block.members_bitset() & unsolved;
+        for line in block.intersecting_lines_iter() {
+            let line_positions = line.members_bitset() & unsolved;
+            let intersection = block_positions & line_positions;
+            if intersection.len() < 2 { continue }
+
+            let line_subsets = subsets(sudoku, line_positions.difference(block_positions));
+            let block_subsets = subsets(sudoku, block_positions.difference(line_positions));
+            for (cells, values) in subsets(sudoku, intersection) {
+                if cells.len() < 2 || values.len() < cells.len() + 2 { continue }
+
+                for &(line_cells, line_values) in &line_subsets {
+                    if (line_values & values).is_empty() { continue }
+                    for &(block_cells, block_values) in &block_subsets {
+                        if (block_values & values).is_empty() || !(block_values & line_values).is_empty() { continue }
+                        if (values | line_values | block_values).len() != cells.len() + line_cells.len() + block_cells.len() {
+                            continue
+                        }
+
+                        let mut excluded_candidates = Vec::new();
+                        let line_excluded = line_values | values.difference(block_values);
+                        for pos in line_positions.difference(cells | line_cells).iter() {
+                            for val in (sudoku.get_candidates_by_pos(pos) & line_excluded).iter() {
+                                excluded_candidates.push((pos, val));
+                            }
+                        }
+                        let block_excluded = block_values | values.difference(line_values);
+                        for pos in block_positions.difference(cells | block_cells).iter() {
+                            for val in (sudoku.get_candidates_by_pos(pos) & block_excluded).iter() {
+                                excluded_candidates.push((pos, val));
+                            }
+                        }
+                        excluded_candidates.sort_unstable();
+                        excluded_candidates.dedup();
+
+                        if !excluded_candidates.is_empty() {
+                            return Some(StrategyResult::SueDeCoq {
+                                excluded_candidates,
+                                block, line,
+                                positions: cells.iter().collect(),
+                                line_positions: line_cells.iter().collect(),
+                                block_positions: block_cells.iter().collect(),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{solver::Line, Value};
+
+    const SUE_DE_COQ_EXAMPLE1: &str = "\
+3     79  6   | 79   5    28  | 1    28   4
+2789  5   4   | 1    29   278 | 3    6    79
+2789  1   279 | 6    3    4   | 789  258  579
+--------------+---------------+--------------
+6     28  3   | 48   7    5   | 249  1    29
+79    4   79  | 2    1    6   | 5    3    8
+5     28  1   | 3    48   9   | 24   7    6
+--------------+---------------+--------------
+4     3   8   | 5    6    27  | 27   9    1
+279   79  5   | 789  28   1   | 6    4    3
+1     6   279 | 479  249  3   | 278  58   257";
+
+    #[test]
+    fn test_sue_de_coq_example1() {
+        let sudoku = Sudoku::from_pencilmarks(SUE_DE_COQ_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::SueDeCoq {
+            excluded_candidates: vec![(Pos::new(9), Value::new(6)), (Pos::new(9), Value::new(8))],
+            block: Block::new(1),
+            line: Line::new(1),
+            positions: vec![Pos::new(13), Pos::new(14)],
+            line_positions: vec![Pos::new(17)],
+            block_positions: vec![Pos::new(5)],
+        };
+        assert_eq!(step_res.to_string(), "Sue de Coq: r2c5,r2c6 with r2c9 in row 2 and r1c6 in block 2 => r2c1<>7, r2c1<>9");
+        assert_eq!(sue_de_coq(&sudoku), Some(step_res));
+    }
+}
@@ -0,0 +1,132 @@
+use super::{Strategy, StrategyResult};
+use crate::{
+    solver::{solver::run_strategies, House, PosBitSet, SolveOpts, ValueBitSet},
+    Pos, Sudoku, Value,
+};
+use std::fmt;
+
+/// Why a sudoku has no solution
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Contradiction {
+    /// An empty cell with no candidates left
+    NoCandidates(Pos),
+    /// A value with nowhere left to go in the house
+    NoPosition(Value, House),
+}
+
+impl fmt::Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Contradiction::NoCandidates(pos) => write!(f, "{} has no candidates", pos),
+            Contradiction::NoPosition(val, house) => write!(f, "{} has no place in {}", val, house),
+        }
+    }
+}
+
+/// An empty cell with no candidates, or a value which isn't in a house and can't go anywhere in it
+pub(crate) fn find_contradiction(sudoku: &Sudoku) -> Option<Contradiction> {
+    for pos in Pos::iter() {
+        if sudoku.get_value(pos).is_none() && sudoku.get_candidates_by_pos(pos).is_empty() {
+            return Some(Contradiction::NoCandidates(pos))
+        }
+    }
+    for house in House::iter() {
+        let placed: ValueBitSet = house.members_iter().filter_map(|pos| sudoku.get_value(pos)).collect();
+        for val in Value::iter().filter(|&val| !placed.contains(val)) {
+            if (sudoku.get_candidates_by_value(val) & house.members_bitset()).is_empty() {
+                return Some(Contradiction::NoPosition(val, house))
+            }
+        }
+    }
+    None
+}
+
+/// Assumes each candidate in turn, and applies up to `max_steps` steps of the strategies. If that
+/// leads to a contradiction, the candidate is excluded. This is expensive: each of the up to 729
+/// candidates costs a copy of the sudoku and up to `max_steps` passes over the strategies, so
+/// `strategies` should be cheap ones.
+pub(crate) fn trial(sudoku: &Sudoku, max_steps: usize, strategies: &'static [Strategy]) -> Option<StrategyResult> {
+    let opts = SolveOpts {
+        strategies,
+        guess_and_check: false,
+        stop_after_first_step: true,
+        // The assumption may well leave more than one solution, or none
+        uniqueness_strategies: false,
+    };
+    let givens: PosBitSet = Pos::iter().filter(|&pos| sudoku.get_value(pos).is_some()).collect();
+    for pos in Pos::iter() {
+        for val in sudoku.get_candidates_by_pos(pos).iter() {
+            let mut sudoku2 = sudoku.clone();
+            sudoku2.set_value(pos, val);
+            let mut steps = Vec::new();
+            loop {
+                if let Some(contradiction) = find_contradiction(&sudoku2) {
+                    return Some(StrategyResult::Trial { pos, value: val, strategies, steps, contradiction });
+                }
+                if steps.len() == max_steps { break }
+
+                let step = match run_strategies(&sudoku2, givens, &opts) {
+                    Some(step) => step,
+                    None => break,
+                };
+                for (pos2, val2) in step.excluded_candidates() {
+                    sudoku2.remove_candidate(pos2, val2);
+                }
+                for (pos2, val2) in step.required_candidates() {
+                    sudoku2.set_value(pos2, val2);
+                }
+                steps.push(step);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{Row, TRIAL};
+
+    const TRIAL_EXAMPLE1: &str = "\
+156  4     3    | 2    56   9  | 7    8   16
+2    679   179  | 137  137  8  | 4    69  5
+8    5679  1579 | 17   56   4  | 19   3   2
+----------------+--------------+-------------
+67   2     68   | 5    4    37 | 38   1   9
+17   38    4    | 9    17   2  | 6    5   38
+159  359   159  | 6    8    13 | 2    4   7
+----------------+--------------+-------------
+45   58    2    | 134  9    6  | 135  7   138
+49   1     689  | 347  37   57 | 58   2   36
+3    567   567  | 8    2    15 | 159  69  4";
+
+    #[test]
+    fn test_trial_example1() {
+        let sudoku = Sudoku::from_pencilmarks(TRIAL_EXAMPLE1).unwrap();
+        let step_res = trial(&sudoku, 20, TRIAL).unwrap();
+        assert_eq!(
+            step_res.to_string(),
+            "Trial: r1c1=5 leads to a contradiction in 19 steps (1 has no place in col 6) => r1c1<>5",
+        );
+        assert_eq!(step_res.excluded_candidates(), vec![(Pos::new(0), Value::new(4))]);
+
+        // Not enough steps to reach the contradiction from any candidate
+        assert_eq!(trial(&sudoku, 3, TRIAL), None);
+    }
+
+    #[test]
+    fn test_find_contradiction() {
+        let mut sudoku = Sudoku::from_pencilmarks(TRIAL_EXAMPLE1).unwrap();
+        assert_eq!(find_contradiction(&sudoku), None);
+
+        // 3 can only go in r5c2 or r5c9 in row 5
+        sudoku.remove_candidate(Pos::new(37), Value::new(2));
+        sudoku.remove_candidate(Pos::new(44), Value::new(2));
+        let contradiction = find_contradiction(&sudoku).unwrap();
+        assert_eq!(contradiction, Contradiction::NoPosition(Value::new(2), House::from_row(Row::new(4))));
+        assert_eq!(contradiction.to_string(), "3 has no place in row 5");
+
+        sudoku.remove_candidate(Pos::new(37), Value::new(7));
+        assert_eq!(find_contradiction(&sudoku), Some(Contradiction::NoCandidates(Pos::new(37))));
+    }
+}
@@ -0,0 +1,623 @@
+use super::StrategyResult;
+use crate::{
+    solver::{Block, Col, House, PosBitSet, Row, ValueBitSet},
+    Pos, Sudoku, Value,
+};
+
+/// Which of the unique rectangle patterns was found
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UniqueRectangleKind {
+    /// Three corners only have the deadly pair, so the fourth can't be either of them
+    Type1,
+    /// The other two corners in a row or column have the same single extra candidate, which must
+    /// be in one of them
+    Type2,
+    /// The extra candidates of the other two corners form a naked subset with other cells in a
+    /// house they share
+    Type3,
+    /// One of the pair is locked into the other two corners in a house they share, so the other
+    /// can't be in either
+    Type4,
+    /// Like type 2, but the corners with the extra candidate are diagonal, or there are three
+    Type5,
+    /// Two diagonal corners only have the pair, and one of them is locked into the rectangle in
+    /// both rows (or columns)
+    Type6,
+    /// One corner only has the pair, and one of them is locked into the rectangle in both the row
+    /// and column of the opposite corner
+    Hidden,
+    /// An avoidable rectangle where three corners were solved (none of them givens), so the fourth
+    /// can't complete the deadly pattern
+    Avoidable1,
+    /// An avoidable rectangle where two corners were solved, and the other two corners have the
+    /// same single extra candidate, which must be in one of them
+    Avoidable2,
+}
+
+/// A rectangle of unsolved cells in exactly two blocks, with two candidates in common. These
+/// corners can't be left with only those two candidates, as they could then be swapped to give
+/// another solution. Corners are in the order top left, top right, bottom left, bottom right, so
+/// `corner ^ 1` is in the same row, `corner ^ 2` is in the same column and `3 - corner` is
+/// diagonally opposite.
+struct Rectangle {
+    positions: [Pos; 4],
+    values: [Value; 2],
+    pair: ValueBitSet,
+    candidates: [ValueBitSet; 4],
+}
+
+impl Rectangle {
+    /// Whether the corner only has the deadly pair left
+    fn is_bivalue(&self, corner: usize) -> bool {
+        self.candidates[corner] == self.pair
+    }
+
+    fn extra_candidates(&self, corner: usize) -> ValueBitSet {
+        self.candidates[corner].difference(self.pair)
+    }
+
+    fn bitset(&self, corners: &[usize]) -> PosBitSet {
+        corners.iter().map(|&corner| self.positions[corner]).collect()
+    }
+
+    /// The row or column containing both corners, and the block too if they share it
+    fn shared_houses(&self, corner1: usize, corner2: usize) -> Vec<House> {
+        let (pos1, pos2) = (self.positions[corner1], self.positions[corner2]);
+        let mut ret = Vec::new();
+        if pos1.row() == pos2.row() {
+            ret.push(House::from_row(Row::new(pos1.row() as usize)));
+        } else if pos1.col() == pos2.col() {
+            ret.push(House::from_col(Col::new(pos1.col() as usize)));
+        }
+        if pos1.block() == pos2.block() {
+            ret.push(House::from_block(Block::from_pos(pos1)));
+        }
+        ret
+    }
+
+    fn result(&self, excluded: PosBitSet, values: ValueBitSet, sudoku: &Sudoku, kind: UniqueRectangleKind)
+        -> Option<StrategyResult>
+    {
+        let mut excluded_candidates = Vec::new();
+        for pos in excluded.iter() {
+            for val in (sudoku.get_candidates_by_pos(pos) & values).iter() {
+                excluded_candidates.push((pos, val));
+            }
+        }
+        if excluded_candidates.is_empty() { return None }
+        Some(StrategyResult::UniqueRectangle {
+            excluded_candidates,
+            positions: self.positions,
+            values: self.values,
+            kind,
+        })
+    }
+}
+
+/// The corners of every rectangle in exactly two blocks. Rectangles in one block can't be deadly
+/// patterns, as swapping the pair doesn't keep the blocks valid.
+fn rectangle_positions() -> impl Iterator<Item = [Pos; 4]> {
+    (0..8).flat_map(|row1| (row1 + 1 .. 9).map(move |row2| (row1, row2)))
+        .flat_map(|(row1, row2)| (0..8).flat_map(move |col1| (col1 + 1 .. 9).map(move |col2| (row1, row2, col1, col2))))
+        .filter(|&(row1, row2, col1, col2)| (row1 / 3 == row2 / 3) != (col1 / 3 == col2 / 3))
+        .map(|(row1, row2, col1, col2)| [
+            Pos::row_col(row1, col1), Pos::row_col(row1, col2),
+            Pos::row_col(row2, col1), Pos::row_col(row2, col2),
+        ])
+}
+
+/// Calls `f` with every possible unique rectangle until it returns `Some`
+fn find_rectangle(sudoku: &Sudoku, mut f: impl FnMut(&Rectangle) -> Option<StrategyResult>) -> Option<StrategyResult> {
+    for positions in rectangle_positions() {
+        if positions.iter().any(|&pos| sudoku.get_value(pos).is_some()) { continue }
+        let candidates = [
+            sudoku.get_candidates_by_pos(positions[0]), sudoku.get_candidates_by_pos(positions[1]),
+            sudoku.get_candidates_by_pos(positions[2]), sudoku.get_candidates_by_pos(positions[3]),
+        ];
+        let common = candidates.iter().fold(ValueBitSet::ALL, |acc, &cands| acc & cands);
+        if common.len() < 2 { continue }
+
+        for val1 in common.iter() {
+            for val2 in common.iter().filter(|&val2| val2 > val1) {
+                let pair: ValueBitSet = [val1, val2].iter().cloned().collect();
+                let rect = Rectangle { positions, values: [val1, val2], pair, candidates };
+                if let res@Some(_) = f(&rect) {
+                    return res;
+                }
+            }
+        }
+    }
+    None
+}
+
+pub(crate) fn unique_rectangle(sudoku: &Sudoku) -> Option<StrategyResult> {
+    find_rectangle(sudoku, |rect| {
+        let bivalue: Vec<usize> = (0..4).filter(|&corner| rect.is_bivalue(corner)).collect();
+        let extra: Vec<usize> = (0..4).filter(|&corner| !rect.is_bivalue(corner)).collect();
+        match bivalue.len() {
+            3 => type_1(sudoku, rect, extra[0]),
+            2 => {
+                let diagonal = extra[0] + extra[1] == 3;
+                type_2_or_5(sudoku, rect, &extra)
+                    .or_else(|| if diagonal { None } else { type_3(sudoku, rect, extra[0], extra[1]) })
+                    .or_else(|| if diagonal { None } else { type_4(sudoku, rect, extra[0], extra[1]) })
+                    .or_else(|| if diagonal { type_6(sudoku, rect, extra[0], extra[1]) } else { None })
+            },
+            1 => type_2_or_5(sudoku, rect, &extra),
+            _ => None
+        }
+    })
+}
+
+pub(crate) fn hidden_unique_rectangle(sudoku: &Sudoku) -> Option<StrategyResult> {
+    find_rectangle(sudoku, |rect| {
+        for corner in (0..4).filter(|&corner| rect.is_bivalue(corner)) {
+            let opposite = 3 - corner;
+            if rect.is_bivalue(opposite) { continue }
+            let row = Row::new(rect.positions[opposite].row() as usize);
+            let col = Col::new(rect.positions[opposite].col() as usize);
+            for (idx, &val) in rect.values.iter().enumerate() {
+                let candidates = sudoku.get_candidates_by_value(val);
+                let in_row = candidates & row.members_bitset() == rect.bitset(&[opposite, opposite ^ 1]);
+                let in_col = candidates & col.members_bitset() == rect.bitset(&[opposite, opposite ^ 2]);
+                if in_row && in_col {
+                    let other: ValueBitSet = [rect.values[1 - idx]].iter().cloned().collect();
+                    let res = rect.result(rect.bitset(&[opposite]), other, sudoku, UniqueRectangleKind::Hidden);
+                    if res.is_some() { return res }
+                }
+            }
+        }
+        None
+    })
+}
+
+fn type_1(sudoku: &Sudoku, rect: &Rectangle, corner: usize) -> Option<StrategyResult> {
+    rect.result(rect.bitset(&[corner]), rect.pair, sudoku, UniqueRectangleKind::Type1)
+}
+
+fn type_2_or_5(sudoku: &Sudoku, rect: &Rectangle, corners: &[usize]) -> Option<StrategyResult> {
+    let extra = rect.extra_candidates(corners[0]);
+    if extra.len() != 1 || corners.iter().any(|&corner| rect.extra_candidates(corner) != extra) { return None }
+
+    let seen_by_all = corners.iter()
+        .fold(PosBitSet::ALL, |acc, &corner| acc & rect.positions[corner].neighbors_bitset());
+    let kind = if corners.len() == 2 && corners[0] + corners[1] != 3 {
+        UniqueRectangleKind::Type2
+    } else {
+        UniqueRectangleKind::Type5
+    };
+    rect.result(seen_by_all, extra, sudoku, kind)
+}
+
+fn type_3(sudoku: &Sudoku, rect: &Rectangle, corner1: usize, corner2: usize) -> Option<StrategyResult> {
+    // One of the extra candidates must be in the corners, so they act like a single cell with all
+    // of them
+    let extra = rect.extra_candidates(corner1) | rect.extra_candidates(corner2);
+    for house in rect.shared_houses(corner1, corner2) {
+        let others: Vec<Pos> = house.members_iter()
+            .filter(|&pos| sudoku.get_value(pos).is_none())
+            .filter(|&pos| pos != rect.positions[corner1] && pos != rect.positions[corner2])
+            .collect();
+        for size in 1..=3 {
+            if extra.len() > size + 1 { continue }
+            let res = find_subset(&others, size, &mut Vec::with_capacity(size), &mut |subset| {
+                let values = subset.iter().fold(extra, |acc, &pos| acc | sudoku.get_candidates_by_pos(pos));
+                if values.len() != size + 1 { return None }
+                let excluded: PosBitSet = others.iter().cloned().filter(|pos| !subset.contains(pos)).collect();
+                rect.result(excluded, values, sudoku, UniqueRectangleKind::Type3)
+            });
+            if res.is_some() { return res }
+        }
+    }
+    None
+}
+
+fn find_subset(positions: &[Pos], size: usize, subset: &mut Vec<Pos>, f: &mut impl FnMut(&[Pos]) -> Option<StrategyResult>)
+    -> Option<StrategyResult>
+{
+    if subset.len() == size {
+        return f(subset);
+    }
+    for (idx, &pos) in positions.iter().enumerate() {
+        subset.push(pos);
+        if let res@Some(_) = find_subset(&positions[idx + 1 ..], size, subset, f) {
+            return res;
+        }
+        subset.pop();
+    }
+    None
+}
+
+fn type_4(sudoku: &Sudoku, rect: &Rectangle, corner1: usize, corner2: usize) -> Option<StrategyResult> {
+    let corners = rect.bitset(&[corner1, corner2]);
+    for house in rect.shared_houses(corner1, corner2) {
+        for (idx, &val) in rect.values.iter().enumerate() {
+            if sudoku.get_candidates_by_value(val) & house.members_bitset() == corners {
+                let other: ValueBitSet = [rect.values[1 - idx]].iter().cloned().collect();
+                return rect.result(corners, other, sudoku, UniqueRectangleKind::Type4);
+            }
+        }
+    }
+    None
+}
+
+fn type_6(sudoku: &Sudoku, rect: &Rectangle, corner1: usize, corner2: usize) -> Option<StrategyResult> {
+    let rows = [Row::new(rect.positions[0].row() as usize), Row::new(rect.positions[3].row() as usize)];
+    let cols = [Col::new(rect.positions[0].col() as usize), Col::new(rect.positions[3].col() as usize)];
+    for &val in &rect.values {
+        let candidates = sudoku.get_candidates_by_value(val);
+        let locked_in_rows = rows.iter()
+            .all(|row| (candidates & row.members_bitset()).difference(rect.bitset(&[0, 1, 2, 3])).is_empty());
+        let locked_in_cols = cols.iter()
+            .all(|col| (candidates & col.members_bitset()).difference(rect.bitset(&[0, 1, 2, 3])).is_empty());
+        if locked_in_rows || locked_in_cols {
+            let val: ValueBitSet = [val].iter().cloned().collect();
+            return rect.result(rect.bitset(&[corner1, corner2]), val, sudoku, UniqueRectangleKind::Type6);
+        }
+    }
+    None
+}
+
+/// Avoidable rectangles are deadly patterns made of solved cells rather than candidates. Only
+/// cells which weren't givens count, since the givens can't be swapped.
+pub(crate) fn avoidable_rectangle(sudoku: &Sudoku, givens: PosBitSet) -> Option<StrategyResult> {
+    for positions in rectangle_positions() {
+        if positions.iter().any(|&pos| givens.contains(pos)) { continue }
+        let values = [
+            sudoku.get_value(positions[0]), sudoku.get_value(positions[1]),
+            sudoku.get_value(positions[2]), sudoku.get_value(positions[3]),
+        ];
+        let result = |excluded: PosBitSet, excluded_val: Value, pair: [Value; 2], kind: UniqueRectangleKind| {
+            let excluded_candidates: Vec<(Pos, Value)> = (excluded & sudoku.get_candidates_by_value(excluded_val)).iter()
+                .map(|pos| (pos, excluded_val))
+                .collect();
+            if excluded_candidates.is_empty() { return None }
+            let values = if pair[0] < pair[1] { pair } else { [pair[1], pair[0]] };
+            Some(StrategyResult::UniqueRectangle { excluded_candidates, positions, values, kind })
+        };
+
+        match values.iter().filter(|val| val.is_none()).count() {
+            1 => {
+                // The solved corner opposite the unsolved one must differ from the other two
+                let corner = values.iter().position(|val| val.is_none()).unwrap();
+                let (val_a, val_b) = (values[3 - corner].unwrap(), values[corner ^ 1].unwrap());
+                if val_a == val_b || values[corner ^ 2] != Some(val_b) { continue }
+                let res = result(positions[corner..corner + 1].iter().cloned().collect(), val_a, [val_a, val_b],
+                    UniqueRectangleKind::Avoidable1);
+                if res.is_some() { return res }
+            },
+            2 => {
+                // The solved corners must be in the same row or column, and the unsolved corner
+                // across from each must be able to complete the pattern, with one extra candidate
+                let solved: Vec<usize> = (0..4).filter(|&corner| values[corner].is_some()).collect();
+                if solved[0] + solved[1] == 3 { continue }
+                let across = |corner: usize| if solved[0] ^ solved[1] == 1 { corner ^ 2 } else { corner ^ 1 };
+                let (val_a, val_b) = (values[solved[0]].unwrap(), values[solved[1]].unwrap());
+                if val_a == val_b { continue }
+                // The corner across from `a` must become `b` and vice versa
+                let (pos_b, pos_a) = (positions[across(solved[0])], positions[across(solved[1])]);
+                let extra_b = sudoku.get_candidates_by_pos(pos_b);
+                let extra_a = sudoku.get_candidates_by_pos(pos_a);
+                if !extra_b.contains(val_b) || !extra_a.contains(val_a) { continue }
+                let extra_b = extra_b.difference([val_b].iter().cloned().collect());
+                let extra_a = extra_a.difference([val_a].iter().cloned().collect());
+                if extra_a != extra_b || extra_a.len() != 1 { continue }
+                let extra = extra_a.iter().next().unwrap();
+                let seen_by_both = pos_a.neighbors_bitset() & pos_b.neighbors_bitset();
+                let res = result(seen_by_both, extra, [val_a, val_b], UniqueRectangleKind::Avoidable2);
+                if res.is_some() { return res }
+            },
+            _ => ()
+        }
+    }
+    None
+}
+
+/// In a Bivalue Universal Grave every unsolved cell has two candidates and every candidate appears
+/// twice in each of its houses, which always has multiple solutions. With a few extra candidates
+/// on top of that (BUG+n), one of the extra candidates must be true, so it's placed if there is
+/// only one, and otherwise anything which sees all of them is removed.
+pub(crate) fn bivalue_universal_grave(sudoku: &Sudoku) -> Option<StrategyResult> {
+    let unsolved: Vec<Pos> = Pos::iter().filter(|&pos| sudoku.get_value(pos).is_none()).collect();
+    if unsolved.is_empty() { return None }
+
+    let count_in = |house: House, val: Value| (sudoku.get_candidates_by_value(val) & house.members_bitset()).len();
+
+    // Candidates appearing more than twice in one of their houses, in cells with more than two
+    let mut extra_candidates = Vec::new();
+    for &pos in &unsolved {
+        let candidates = sudoku.get_candidates_by_pos(pos);
+        match candidates.len() {
+            0 | 1 => return None,
+            2 => continue,
+            _ => ()
+        }
+        let mut remaining = 0;
+        for val in candidates.iter() {
+            if House::containing(pos).iter().any(|&house| count_in(house, val) > 2) {
+                extra_candidates.push((pos, val));
+            } else {
+                remaining += 1;
+            }
+        }
+        if remaining != 2 { return None }
+    }
+    if extra_candidates.is_empty() { return None }
+
+    // Without the extra candidates we must be left with exactly a BUG
+    for house in House::iter() {
+        for val in Value::iter() {
+            let extra = extra_candidates.iter()
+                .filter(|&&(pos, val2)| val == val2 && house.members_bitset().contains(pos))
+                .count();
+            match count_in(house, val) - extra {
+                0 | 2 => (),
+                _ => return None
+            }
+        }
+    }
+
+    if extra_candidates.len() == 1 {
+        return Some(StrategyResult::BivalueUniversalGrave {
+            excluded_candidates: Vec::new(),
+            required_candidates: extra_candidates.clone(),
+            extra_candidates,
+        });
+    }
+
+    // A candidate sees an extra candidate if it's in the same cell, or has the same value in a
+    // neighboring cell
+    let sees = |(pos, val): (Pos, Value), (pos2, val2): (Pos, Value)| {
+        (pos == pos2 && val != val2) || (val == val2 && pos.neighbors_bitset().contains(pos2))
+    };
+    let mut excluded_candidates = Vec::new();
+    for &pos in &unsolved {
+        for val in sudoku.get_candidates_by_pos(pos).iter() {
+            if extra_candidates.contains(&(pos, val)) { continue }
+            if extra_candidates.iter().all(|&extra| sees((pos, val), extra)) {
+                excluded_candidates.push((pos, val));
+            }
+        }
+    }
+    if excluded_candidates.is_empty() { return None }
+    Some(StrategyResult::BivalueUniversalGrave {
+        excluded_candidates,
+        required_candidates: Vec::new(),
+        extra_candidates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{solve, tests::check_example, SolveOpts, SolveResult, Strategy};
+
+    #[test]
+    fn test_type_1_example1() {
+        let line = "75...2.464..1.....8..4.....289516437367948521145723689.726.4......2.7.6.6....127.";
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(25), Value::new(4)), (Pos::new(25), Value::new(8))],
+            positions: [Pos::new(14), Pos::new(16), Pos::new(23), Pos::new(25)],
+            values: [Value::new(4), Value::new(8)],
+            kind: UniqueRectangleKind::Type1,
+        };
+        check_example(unique_rectangle, line, Some(step_res));
+    }
+
+    #[test]
+    fn test_type_2_example1() {
+        let line = ".2.8..459.4.6..2.......41....1...8342.43.1.9.3.947..12.927..3.1438169.2..17...9..";
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(37), Value::new(5)), (Pos::new(51), Value::new(5))],
+            positions: [Pos::new(42), Pos::new(44), Pos::new(69), Pos::new(71)],
+            values: [Value::new(4), Value::new(6)],
+            kind: UniqueRectangleKind::Type2,
+        };
+        check_example(unique_rectangle, line, Some(step_res));
+    }
+
+    #[test]
+    fn test_type_3_example1() {
+        let line = "72....5.885.......164.5..7.682.1..5.415....8.937...1...481796...71...8493964..7..";
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(61), Value::new(1))],
+            positions: [Pos::new(2), Pos::new(7), Pos::new(11), Pos::new(16)],
+            values: [Value::new(2), Value::new(8)],
+            kind: UniqueRectangleKind::Type3,
+        };
+        check_example(unique_rectangle, line, Some(step_res));
+    }
+
+    #[test]
+    fn test_type_4_example1() {
+        let line = "5..7....9.36.5287...7....5.12...7.85.5..2961776..15.23.85.7..9....58473.37..9.5.8";
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(29), Value::new(3)), (Pos::new(47), Value::new(3))],
+            positions: [Pos::new(29), Pos::new(33), Pos::new(47), Pos::new(51)],
+            values: [Value::new(3), Value::new(8)],
+            kind: UniqueRectangleKind::Type4,
+        };
+        check_example(unique_rectangle, line, Some(step_res));
+    }
+
+    #[test]
+    fn test_type_5_example1() {
+        let sudoku = Sudoku::from_pencilmarks("\
+1     458   38 | 9   7    246 | 23458  246  23458
+6     7     2  | 8   45   3   | 459    1    459
+348   458   9  | 16  145  26  | 7      246  23458
+---------------+--------------+------------------
+238   28    7  | 36  9    46  | 15     24   15
+5     469   36 | 2   8    1   | 3469   79   4679
+2349  2469  1  | 7   34   5   | 23469  8    23469
+---------------+--------------+------------------
+79    1     5  | 4   2    8   | 69     3    679
+2789  2689  68 | 13  13   79  | 248    5    24
+28    3     4  | 5   6    79  | 128    79   128").unwrap();
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(6), Value::new(3)), (Pos::new(8), Value::new(3))],
+            positions: [Pos::new(5), Pos::new(7), Pos::new(23), Pos::new(25)],
+            values: [Value::new(1), Value::new(5)],
+            kind: UniqueRectangleKind::Type5,
+        };
+        assert_eq!(unique_rectangle(&sudoku), Some(step_res));
+    }
+
+    #[test]
+    fn test_type_6_example1() {
+        let line = "374.....881....37556.738..4758.9..4323684.9.79413.7..66.3.8.7..1.5.734..4.7..9.3.";
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(62), Value::new(8)), (Pos::new(64), Value::new(8))],
+            positions: [Pos::new(55), Pos::new(62), Pos::new(64), Pos::new(71)],
+            values: [Value::new(1), Value::new(8)],
+            kind: UniqueRectangleKind::Type6,
+        };
+        check_example(unique_rectangle, line, Some(step_res));
+    }
+
+    #[test]
+    fn test_hidden_example1() {
+        let line = "....7.48148......7.718.46.9.2.5..314.1..9..62.6.....9513.649.78847..19.6...7.8143";
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(47), Value::new(2))],
+            positions: [Pos::new(38), Pos::new(39), Pos::new(47), Pos::new(48)],
+            values: [Value::new(2), Value::new(3)],
+            kind: UniqueRectangleKind::Hidden,
+        };
+        check_example(hidden_unique_rectangle, line, Some(step_res));
+    }
+
+    #[test]
+    fn test_disable_uniqueness_strategies() {
+        let sudoku = Sudoku::from_line("75...2.464..1.....8..4.....289516437367948521145723689.726.4......2.7.6.6....127.").unwrap();
+        let mut opts = SolveOpts {
+            strategies: &[Strategy::UniqueRectangle],
+            guess_and_check: false,
+            stop_after_first_step: true,
+            uniqueness_strategies: true,
+        };
+        assert_eq!(solve(sudoku.clone(), &opts).steps.len(), 1);
+        opts.uniqueness_strategies = false;
+        assert!(solve(sudoku, &opts).steps.is_empty());
+    }
+
+    #[test]
+    fn test_no_uniqueness_strategies_by_default() {
+        // Each has several solutions, but BUG+1 or an avoidable rectangle would leave only one
+        let examples = [
+            ("4.......838..........6............3.574.6.........7..29...18.6...83...4..6.4.51.7", Strategy::BivalueUniversalGrave),
+            ("3..........1..79....4..6..27.8...19...3.....4.......37......54..4..9.....278....6", Strategy::AvoidableRectangle),
+        ];
+        for &(line, strategy) in &examples {
+            let sudoku = Sudoku::from_line(line).unwrap();
+            let requires_uniqueness = |res: &SolveResult| res.steps.iter()
+                .any(|step| step.strategy().is_some_and(|strat| strat.requires_uniqueness()));
+
+            let res = solve(sudoku.clone(), &SolveOpts::default());
+            assert!(res.is_non_unique());
+            assert!(!requires_uniqueness(&res));
+
+            let res = solve(sudoku, &SolveOpts { uniqueness_strategies: true, ..SolveOpts::default() });
+            assert!(res.is_unique());
+            assert!(res.steps.iter().any(|step| step.strategy() == Some(strategy)));
+        }
+    }
+
+    #[test]
+    fn test_avoidable_type_1_example1() {
+        let givens = Sudoku::from_line("5....6........2.1691..3.....7.....3..4..2.5....87..2.4.....78...3.....92....9.6..").unwrap();
+        let givens = Pos::iter().filter(|&pos| givens.get_value(pos).is_some()).collect();
+        let sudoku = Sudoku::from_pencilmarks("\
+5    2  347 | 19   17  6  | 349  48  89
+47   8  347 | 59   57  2  | 349  1   6
+9    1  6   | 4    3   8  | 7    2   5
+------------+-------------+------------
+2    7  5   | 6    8   4  | 19   3   19
+6    4  1   | 3    2   9  | 5    78  78
+3    9  8   | 7    15  15 | 2    6   4
+------------+-------------+------------
+1    6  9   | 2    4   7  | 8    5   3
+478  3  47  | 158  6   15 | 14   9   2
+48   5  2   | 18   9   3  | 6    47  17").unwrap();
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(66), Value::new(7))],
+            positions: [Pos::new(30), Pos::new(31), Pos::new(66), Pos::new(67)],
+            values: [Value::new(5), Value::new(7)],
+            kind: UniqueRectangleKind::Avoidable1,
+        };
+        assert_eq!(avoidable_rectangle(&sudoku, givens), Some(step_res));
+        // Without knowing which cells were given the rectangle can't be used
+        let all_solved = Pos::iter().filter(|&pos| sudoku.get_value(pos).is_some()).collect();
+        assert_eq!(avoidable_rectangle(&sudoku, all_solved), None);
+    }
+
+    #[test]
+    fn test_avoidable_type_2_example1() {
+        let givens = Sudoku::from_line(".8....1.44.752......68...5......3...6..4..31..7......9....5.49...2.7.5..8..3....2").unwrap();
+        let givens = Pos::iter().filter(|&pos| givens.get_value(pos).is_some()).collect();
+        let sudoku = Sudoku::from_pencilmarks("\
+5  8    9   | 6  3    7   | 1    2    4
+4  13   7   | 5  2    19  | 689  68   36
+2  13   6   | 8  149  149 | 79   5    37
+------------+-------------+-------------
+9  125  148 | 7  68   3   | 268  468  56
+6  25   8   | 4  89   25  | 3    1    57
+3  7    48  | 1  68   25  | 268  468  9
+------------+-------------+-------------
+7  6    3   | 2  5    8   | 4    9    1
+1  4    2   | 9  7    6   | 5    3    8
+8  9    5   | 3  14   14  | 67   67   2").unwrap();
+        let step_res = StrategyResult::UniqueRectangle {
+            excluded_candidates: vec![(Pos::new(15), Value::new(5))],
+            positions: [Pos::new(16), Pos::new(17), Pos::new(70), Pos::new(71)],
+            values: [Value::new(2), Value::new(7)],
+            kind: UniqueRectangleKind::Avoidable2,
+        };
+        assert_eq!(step_res.to_string(), "Avoidable Rectangle Type 2: 3/8 at r2c8,r2c9,r8c8,r8c9 => r2c7<>6");
+        assert_eq!(avoidable_rectangle(&sudoku, givens), Some(step_res));
+    }
+
+    #[test]
+    fn test_bug_plus_1_example1() {
+        let sudoku = Sudoku::from_pencilmarks("\
+8  2   7  | 9   6    1  | 5   3  4
+1  6   9  | 35  35   4  | 7   2  8
+4  3   5  | 8   2    7  | 6   1  9
+----------+-------------+---------
+6  49  24 | 12  19   5  | 8   7  3
+3  89  28 | 27  79   6  | 1   4  5
+5  7   1  | 4   38   38 | 9   6  2
+----------+-------------+---------
+2  15  48 | 6   15   38 | 34  9  7
+9  48  6  | 37  378  2  | 34  5  1
+7  15  3  | 15  4    9  | 2   8  6").unwrap();
+        let step_res = StrategyResult::BivalueUniversalGrave {
+            excluded_candidates: Vec::new(),
+            required_candidates: vec![(Pos::new(67), Value::new(2))],
+            extra_candidates: vec![(Pos::new(67), Value::new(2))],
+        };
+        assert_eq!(bivalue_universal_grave(&sudoku), Some(step_res));
+    }
+
+    #[test]
+    fn test_bug_plus_2_example1() {
+        let sudoku = Sudoku::from_pencilmarks("\
+7   15  4  | 2    56  156 | 9  3   8
+9   15  8  | 15   3   4   | 7  2   6
+2   6   3  | 89   78  79  | 4  1   5
+-----------+--------------+---------
+18  9   17 | 3    68  2   | 5  67  4
+6   4   2  | 57   1   57  | 3  8   9
+58  3   57 | 89   4   69  | 2  67  1
+-----------+--------------+---------
+4   8   15 | 157  57  3   | 6  9   2
+15  7   9  | 6    2   15  | 8  4   3
+3   2   6  | 4    9   8   | 1  5   7").unwrap();
+        let step_res = StrategyResult::BivalueUniversalGrave {
+            excluded_candidates: vec![(Pos::new(12), Value::new(4)), (Pos::new(68), Value::new(4))],
+            required_candidates: Vec::new(),
+            extra_candidates: vec![(Pos::new(5), Value::new(4)), (Pos::new(57), Value::new(4))],
+        };
+        assert_eq!(bivalue_universal_grave(&sudoku), Some(step_res));
+    }
+}
@@ -1,12 +1,20 @@
-use super::StrategyResult;
+use super::{als::common_neighbors, coloring::conjugate_pairs, StrategyResult};
 use crate::{
-    Sudoku, Pos,
+    solver::{PosBitSet, ValueBitSet},
+    Sudoku, Pos, Value,
 };
 
+/// The most cells `wing` looks for, one for each value
+pub(crate) const MAX_WING_SIZE: usize = Value::N;
+
+/// Cells with exactly two candidates
+pub(crate) fn bivalue_cells(sudoku: &Sudoku) -> PosBitSet {
+    Pos::iter().filter(|&pos| sudoku.get_candidates_by_pos(pos).len() == 2).collect()
+}
+
 pub(crate) fn xy_wing(sudoku: &Sudoku) -> Option<StrategyResult> {
-    for xypos in Pos::iter() {
+    for xypos in bivalue_cells(sudoku).iter() {
         let xyvals = sudoku.get_candidates_by_pos(xypos);
-        if xyvals.len() != 2 { continue }
 
         let mut outer_iter = xypos.neighbors_iter();
         while let Some(xzpos) = outer_iter.next() {
@@ -137,13 +145,160 @@ pub(crate) fn wxyz_wing(sudoku: &Sudoku) -> Option<StrategyResult> {
     None
 }
 
+/// The candidates of z seeing every cell of the wing with z
+fn wing_excluded_positions(sudoku: &Sudoku, wing: PosBitSet, z: Value) -> PosBitSet {
+    let candidates = sudoku.get_candidates_by_value(z);
+    common_neighbors(wing & candidates) & candidates
+}
+
+/// Adds cells from `cells` to the wing until it has `size` cells, skipping any which would give it
+/// too many candidates, more than one unrestricted value, or nothing to exclude
+fn extend_wing(sudoku: &Sudoku, size: usize, cells: &[Pos], wing: PosBitSet, values: ValueBitSet,
+    unrestricted: ValueBitSet) -> Option<StrategyResult>
+{
+    if wing.len() == size {
+        let z = unrestricted.iter().next()?;
+        if values.len() != size { return None }
+        return Some(StrategyResult::Wing {
+            excluded_candidates: wing_excluded_positions(sudoku, wing, z).iter().map(|pos| (pos, z)).collect(),
+            positions: wing.iter().collect(),
+            values: values.iter().filter(|&val| val != z).chain(Some(z)).collect(),
+        })
+    }
+    if wing.len() + cells.len() < size { return None }
+
+    for (idx, &pos) in cells.iter().enumerate() {
+        let candidates = sudoku.get_candidates_by_pos(pos);
+        let values2 = values | candidates;
+        if values2.len() > size { continue }
+        // A value stops being restricted once any two of its cells don't see each other
+        let unrestricted2 = unrestricted | candidates.iter()
+            .filter(|&val| !(wing & sudoku.get_candidates_by_value(val)).difference(pos.neighbors_bitset()).is_empty())
+            .collect();
+        if unrestricted2.len() > 1 { continue }
+        let mut wing2 = wing;
+        wing2.insert(pos);
+        // More cells with z can only see fewer cells
+        if let Some(z) = unrestricted2.iter().next() {
+            if wing_excluded_positions(sudoku, wing2, z).is_empty() { continue }
+        }
+
+        if let ret@Some(_) = extend_wing(sudoku, size, &cells[idx + 1 ..], wing2, values2, unrestricted2) { return ret }
+    }
+    None
+}
+
+/// As many cells as they have candidates between them, where each value but one, z, is
+/// restricted: its cells all see each other, so it's in at most one of them. The other values
+/// can't fill every cell, so z is in at least one, and is excluded from cells seeing all of those.
+/// XY, XYZ and WXYZ-Wings are the three and four cell cases, so this finds those too, only slower.
+pub(crate) fn wing(sudoku: &Sudoku, max_size: usize) -> Option<StrategyResult> {
+    for size in 3 ..= max_size.min(MAX_WING_SIZE) {
+        let cells: Vec<Pos> = Pos::iter()
+            .filter(|&pos| (2 ..= size).contains(&sudoku.get_candidates_by_pos(pos).len()))
+            .collect();
+        if let ret@Some(_) = extend_wing(sudoku, size, &cells, PosBitSet::NONE, ValueBitSet::NONE, ValueBitSet::NONE) {
+            return ret
+        }
+    }
+    None
+}
+
+/// Two cells with the same two candidates which don't see each other, and a strong link on one of
+/// the values with one end seeing each cell. The link stops both cells having that value, so one
+/// of them has the other value, which is excluded from cells seeing both.
+pub(crate) fn w_wing(sudoku: &Sudoku) -> Option<StrategyResult> {
+    let bivalue = bivalue_cells(sudoku);
+    for pos1 in bivalue.iter() {
+        let values = sudoku.get_candidates_by_pos(pos1);
+        for pos2 in bivalue.iter() {
+            if pos2 <= pos1 || sudoku.get_candidates_by_pos(pos2) != values { continue }
+            if pos1.neighbors_bitset().contains(pos2) { continue }
+            let neighbors = pos1.neighbors_bitset() & pos2.neighbors_bitset();
+
+            for x in values.iter() {
+                let y = values.iter().find(|&val| val != x).unwrap();
+                let excluded_positions = neighbors & sudoku.get_candidates_by_value(y);
+                if excluded_positions.is_empty() { continue }
+
+                for (link1, link2) in conjugate_pairs(sudoku.get_candidates_by_value(x)) {
+                    for &(end1, end2) in &[(link1, link2), (link2, link1)] {
+                        if pos1.neighbors_bitset().contains(end1) && pos2.neighbors_bitset().contains(end2) {
+                            return Some(StrategyResult::WWing {
+                                excluded_candidates: excluded_positions.iter().map(|pos| (pos, y)).collect(),
+                                positions: [pos1, pos2],
+                                values: [x, y],
+                                link: [end1, end2]
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// A chain of cells with the same two candidates, each seeing the next, so the values alternate
+/// along it. The ends of a chain with an even number of cells have different values, so cells
+/// seeing both can have neither.
+pub(crate) fn remote_pair(sudoku: &Sudoku) -> Option<StrategyResult> {
+    let bivalue = bivalue_cells(sudoku);
+    for start in bivalue.iter() {
+        let values = sudoku.get_candidates_by_pos(start);
+        let cells: PosBitSet = bivalue.iter().filter(|&pos| sudoku.get_candidates_by_pos(pos) == values).collect();
+        if cells.len() < 4 { continue }
+
+        // Breadth first, so the chain to each cell is the shortest, recording the previous cell
+        let mut visited = PosBitSet::NONE;
+        visited.insert(start);
+        let mut chains: Vec<(Pos, usize)> = vec![(start, 0)];
+        let mut idx = 0;
+        while idx < chains.len() {
+            let pos = chains[idx].0;
+            for next in (cells & pos.neighbors_bitset()).difference(visited).iter() {
+                visited.insert(next);
+                chains.push((next, idx));
+            }
+            idx += 1;
+        }
+
+        for idx in 1 .. chains.len() {
+            let end = chains[idx].0;
+            if end < start { continue }
+            let mut chain = vec![end];
+            let mut prev = idx;
+            while prev != 0 {
+                prev = chains[prev].1;
+                chain.push(chains[prev].0);
+            }
+            if chain.len() < 4 || chain.len() % 2 != 0 { continue }
+
+            let mut excluded_candidates = Vec::new();
+            for pos in (start.neighbors_bitset() & end.neighbors_bitset()).difference(cells).iter() {
+                for val in (sudoku.get_candidates_by_pos(pos) & values).iter() {
+                    excluded_candidates.push((pos, val));
+                }
+            }
+
+            if !excluded_candidates.is_empty() {
+                let mut values_iter = values.iter();
+                chain.reverse();
+                return Some(StrategyResult::RemotePair {
+                    excluded_candidates,
+                    values: [values_iter.next().unwrap(), values_iter.next().unwrap()],
+                    chain
+                });
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{
-        solver::tests::check_example,
-        Value,
-    };
+    use crate::solver::tests::check_example;
 
     #[test]
     fn test_xy_wing_example1() {
@@ -190,4 +345,109 @@ mod tests {
                 values: [Value::new(8), Value::new(4), Value::new(7), Value::new(6)]
             }));
     }
+
+    const W_WING_EXAMPLE1: &str = "\
+8   36   2369 | 45    1    245 | 47  39   37
+17  4    137  | 8     6    9   | 5   13   2
+5   12   129  | 7     24   3   | 8   149  6
+--------------+----------------+------------
+4   36   1367 | 13    8    27  | 12  5    9
+9   5    138  | 6     234  24  | 12  7    48
+17  127  1278 | 1459  59   457 | 3   6    48
+--------------+----------------+------------
+6   8    45   | 259   59   1   | 47  234  37
+3   9    45   | 25    7    8   | 6   24   1
+2   17   17   | 34    34   6   | 9   8    5";
+
+    #[test]
+    fn test_w_wing_example1() {
+        let sudoku = Sudoku::from_pencilmarks(W_WING_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::WWing {
+            excluded_candidates: vec![(Pos::new(57), Value::new(4))],
+            positions: [Pos::new(3), Pos::new(56)],
+            values: [Value::new(3), Value::new(4)],
+            link: [Pos::new(6), Pos::new(60)]
+        };
+        assert_eq!(step_res.to_string(), "W-Wing 4/5 at r1c4,r7c3 connected by r1c7=r7c7 => r7c4<>5");
+        assert_eq!(w_wing(&sudoku), Some(step_res));
+    }
+
+    const REMOTE_PAIR_EXAMPLE1: &str = "\
+1   2  4  | 3  7   9  | 568  56  68
+7   6  3  | 5  8   4  | 9    1   2
+8   5  9  | 1  6   2  | 37   4   37
+----------+-----------+------------
+4   9  56 | 2  35  7  | 36   8   1
+56  8  7  | 4  35  1  | 2    9   36
+2   3  1  | 6  9   8  | 4    7   5
+----------+-----------+------------
+3   1  2  | 9  4   56 | 78   56  78
+56  4  8  | 7  1   3  | 56   2   9
+9   7  56 | 8  2   56 | 1    3   4";
+
+    #[test]
+    fn test_remote_pair_example1() {
+        let sudoku = Sudoku::from_pencilmarks(REMOTE_PAIR_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::RemotePair {
+            excluded_candidates: vec![(Pos::new(33), Value::new(5))],
+            values: [Value::new(4), Value::new(5)],
+            chain: vec![Pos::new(29), Pos::new(36), Pos::new(63), Pos::new(69)]
+        };
+        assert_eq!(step_res.to_string(), "Remote Pair 5/6 at r4c3,r5c1,r8c1,r8c7 => r4c7<>6");
+        assert_eq!(remote_pair(&sudoku), Some(step_res));
+    }
+
+    const WING_EXAMPLE1: &str = "\
+156   4     3     | 2    56    9   | 7     8   16
+2     679   179   | 137  1367  8   | 4     69  5
+8     5679  15679 | 17   1567  4   | 19    3   2
+------------------+----------------+--------------
+67    2     678   | 5    4     37  | 38    1   9
+17    378   4     | 9    137   2   | 6     5   38
+159   359   159   | 6    8     13  | 2     4   7
+------------------+----------------+--------------
+45    58    2     | 134  9     6   | 135   7   138
+4579  1     56789 | 347  37    357 | 3589  2   36
+3     5679  5679  | 8    2     157 | 159   69  4";
+
+    #[test]
+    fn test_wing_example1() {
+        let sudoku = Sudoku::from_pencilmarks(WING_EXAMPLE1).unwrap();
+        let step_res = StrategyResult::Wing {
+            excluded_candidates: vec![(Pos::new(13), Value::new(5))],
+            positions: vec![Pos::new(4), Pos::new(16), Pos::new(21), Pos::new(22), Pos::new(24)],
+            values: vec![Value::new(0), Value::new(4), Value::new(6), Value::new(8), Value::new(5)]
+        };
+        assert_eq!(step_res.to_string(), "VWXYZ-Wing 1/5/7/9/6 at r1c5,r2c8,r3c4,r3c5,r3c7 => r2c5<>6");
+        assert_eq!(wing(&sudoku, 4), None);
+        assert_eq!(wing(&sudoku, 5), Some(step_res.clone()));
+        // Smaller wings are found first
+        assert_eq!(wing(&sudoku, MAX_WING_SIZE), Some(step_res));
+    }
+
+    const WING_EXAMPLE2: &str = "\
+8  23  57 | 257  4   6   | 357  9  1
+9  23  4  | 1    35  237 | 6    8  57
+1  6   57 | 8    9   357 | 357  2  4
+----------+--------------+-----------
+7  4   9  | 25   35  235 | 1    6  8
+5  1   3  | 9    6   8   | 4    7  2
+6  8   2  | 47   1   47  | 59   3  59
+----------+--------------+-----------
+2  57  6  | 45   8   459 | 79   1  3
+4  9   8  | 3    7   1   | 2    5  6
+3  57  1  | 6    2   59  | 8    4  79";
+
+    #[test]
+    fn test_wing_example2() {
+        let sudoku = Sudoku::from_pencilmarks(WING_EXAMPLE2).unwrap();
+        let step_res = StrategyResult::Wing {
+            excluded_candidates: vec![(Pos::new(23), Value::new(4))],
+            positions: vec![Pos::new(10), Pos::new(13), Pos::new(14), Pos::new(50), Pos::new(59), Pos::new(77)],
+            values: vec![Value::new(1), Value::new(2), Value::new(3), Value::new(6), Value::new(8), Value::new(4)]
+        };
+        assert_eq!(step_res.to_string(), "UVWXYZ-Wing 2/3/4/7/9/5 at r2c2,r2c5,r2c6,r6c6,r7c6,r9c6 => r3c6<>5");
+        assert_eq!(wing(&sudoku, 5), None);
+        assert_eq!(wing(&sudoku, 6), Some(step_res));
+    }
 }
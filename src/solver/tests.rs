@@ -16,6 +16,7 @@ pub(crate) fn solve_expected_step(sudoku: &Sudoku, strategies: &[Strategy]) -> S
         strategies: strategies,
         guess_and_check: false,
         stop_after_first_step: true,
+        uniqueness_strategies: true,
     };
     solve(sudoku.clone(), &opts).steps.into_iter().next().unwrap()
 }
@@ -59,16 +60,19 @@ pub(crate) fn generate_example(
         strategies: &allowed_before_strats,
         guess_and_check: false,
         stop_after_first_step: false,
+        uniqueness_strategies: true,
     };
     let required_next_opts = SolveOpts {
         strategies: required_next_strats,
         guess_and_check: false,
         stop_after_first_step: true,
+        uniqueness_strategies: true,
     };
     let disallowed_next_opts = SolveOpts {
         strategies: &disallowed_next_strats,
         guess_and_check: false,
         stop_after_first_step: true,
+        uniqueness_strategies: true,
     };
     loop {
         let sudoku = generate(Default::default());